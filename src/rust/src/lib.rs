@@ -1,12 +1,26 @@
 use extendr_api::prelude::*;
-use crate::ndarray::{ArrayD, IxDyn};
+use crate::ndarray::{concatenate, Array1, ArrayD, ArrayViewD, Axis, IxDyn, Slice};
 
 #[cfg(not(target_arch = "wasm32"))]
 use ort::execution_providers::ExecutionProviderDispatch;
 #[cfg(not(target_arch = "wasm32"))]
-use ort::session::{builder::GraphOptimizationLevel, Session, SessionOutputs};
+use ort::session::{builder::GraphOptimizationLevel, Session, SessionInputValue, SessionInputs, SessionOutputs};
 #[cfg(not(target_arch = "wasm32"))]
-use ort::value::{Tensor, Value};
+use ort::io_binding::IoBinding;
+#[cfg(not(target_arch = "wasm32"))]
+use ort::memory::{Allocator, MemoryInfo};
+#[cfg(not(target_arch = "wasm32"))]
+use ort::operator::io::{OperatorInput, OperatorOutput};
+#[cfg(not(target_arch = "wasm32"))]
+use ort::operator::kernel::{Kernel, KernelAttributes, KernelContext};
+#[cfg(not(target_arch = "wasm32"))]
+use ort::operator::{Operator, OperatorDomain};
+#[cfg(not(target_arch = "wasm32"))]
+use ort::tensor::{PrimitiveTensorElementType, TensorElementType};
+#[cfg(not(target_arch = "wasm32"))]
+use ort::value::{DynTensor, DynValue, DynValueTypeMarker, Map, Sequence, Tensor, TensorRef, Value, ValueType};
+#[cfg(not(target_arch = "wasm32"))]
+use ort::AsPointer;
 
 #[cfg(target_arch = "wasm32")]
 pub struct Session;
@@ -18,13 +32,215 @@ pub struct Tensor;
 pub struct SessionOutputs;
 #[cfg(target_arch = "wasm32")]
 pub struct ExecutionProviderDispatch;
+#[cfg(target_arch = "wasm32")]
+pub struct IoBinding;
+#[cfg(target_arch = "wasm32")]
+pub struct DynTensor;
 
-use std::collections::HashMap;
+use std::cell::Cell;
+use std::collections::{HashMap, VecDeque};
+use std::ffi::{c_char, c_int, c_void, CStr};
 use std::fmt;
-use std::path::Path;
-use std::sync::Once;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::panic::AssertUnwindSafe;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Condvar, Mutex, MutexGuard, TryLockError};
+use std::time::{Duration, Instant, SystemTime};
+
+static ORT_RUNTIME: Mutex<RuntimeState> = Mutex::new(RuntimeState {
+    outcome: None,
+    committed_path: None,
+    session_created: false,
+});
+
+/// Process-wide ONNX Runtime initialization state
+struct RuntimeState {
+    /// Outcome of the last initialization attempt, holding the library path on success
+    outcome: Option<ChurOnResult<String>>,
+    /// Library path handed to `ort`, which cannot change for the rest of the process
+    committed_path: Option<String>,
+    session_created: bool,
+}
+
+/// Numbers sessions so their log messages can be told apart
+static SESSION_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+/// Log messages from the ONNX Runtime environment itself, as opposed to a session
+static ENVIRONMENT_LOG: LogBuffer = LogBuffer::new(LogSeverity::Warning);
+
+/// Most messages a log buffer keeps before dropping the oldest
+const LOG_CAPACITY: usize = 1000;
+
+/// ONNX Runtime log severity, ordered from most to least verbose
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum LogSeverity {
+    Verbose,
+    Info,
+    Warning,
+    Error,
+    Fatal,
+}
+
+impl LogSeverity {
+    const ALL: [LogSeverity; 5] = [
+        LogSeverity::Verbose,
+        LogSeverity::Info,
+        LogSeverity::Warning,
+        LogSeverity::Error,
+        LogSeverity::Fatal,
+    ];
+
+    pub fn parse(level: &str) -> ChurOnResult<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|severity| severity.as_str() == level.to_lowercase())
+            .ok_or_else(|| {
+                ChurOnError::Validation(format!(
+                    "log_level must be one of \"verbose\", \"info\", \"warning\", \"error\" or \"fatal\", got \"{}\"",
+                    level
+                ))
+            })
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            LogSeverity::Verbose => "verbose",
+            LogSeverity::Info => "info",
+            LogSeverity::Warning => "warning",
+            LogSeverity::Error => "error",
+            LogSeverity::Fatal => "fatal",
+        }
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn to_ort(self) -> ort::logging::LogLevel {
+        match self {
+            LogSeverity::Verbose => ort::logging::LogLevel::Verbose,
+            LogSeverity::Info => ort::logging::LogLevel::Info,
+            LogSeverity::Warning => ort::logging::LogLevel::Warning,
+            LogSeverity::Error => ort::logging::LogLevel::Error,
+            LogSeverity::Fatal => ort::logging::LogLevel::Fatal,
+        }
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn from_ort(level: ort::logging::LogLevel) -> Self {
+        match level {
+            ort::logging::LogLevel::Verbose => LogSeverity::Verbose,
+            ort::logging::LogLevel::Info => LogSeverity::Info,
+            ort::logging::LogLevel::Warning => LogSeverity::Warning,
+            ort::logging::LogLevel::Error => LogSeverity::Error,
+            ort::logging::LogLevel::Fatal => LogSeverity::Fatal,
+        }
+    }
+}
+
+/// One message received from ONNX Runtime's logger
+#[derive(Debug, Clone)]
+struct LogEntry {
+    seq: u64,
+    severity: LogSeverity,
+    logger_id: String,
+    location: String,
+    message: String,
+}
+
+/// Bounded ring buffer of ONNX Runtime log messages. Loggers are called on ORT's
+/// own threads, where R must not be touched, so messages wait here until R asks.
+struct LogBuffer {
+    min_severity: AtomicUsize,
+    state: Mutex<LogState>,
+}
+
+struct LogState {
+    entries: VecDeque<LogEntry>,
+    next_seq: u64,
+    /// Entries numbered below this have already been forwarded to R
+    forwarded: u64,
+}
+
+impl LogBuffer {
+    const fn new(min_severity: LogSeverity) -> Self {
+        LogBuffer {
+            min_severity: AtomicUsize::new(min_severity as usize),
+            state: Mutex::new(LogState {
+                entries: VecDeque::new(),
+                next_seq: 0,
+                forwarded: 0,
+            }),
+        }
+    }
+
+    fn min_severity(&self) -> LogSeverity {
+        LogSeverity::ALL[self.min_severity.load(Ordering::Relaxed)]
+    }
+
+    fn set_min_severity(&self, severity: LogSeverity) -> LogSeverity {
+        LogSeverity::ALL[self.min_severity.swap(severity as usize, Ordering::Relaxed)]
+    }
+
+    fn lock(&self) -> MutexGuard<'_, LogState> {
+        // A panic while holding the lock cannot leave the buffer inconsistent
+        self.state.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    fn push(&self, severity: LogSeverity, logger_id: &str, location: &str, message: &str) {
+        if severity < self.min_severity() {
+            return;
+        }
+        let mut state = self.lock();
+        if state.entries.len() == LOG_CAPACITY {
+            state.entries.pop_front();
+        }
+        let seq = state.next_seq;
+        state.next_seq += 1;
+        state.entries.push_back(LogEntry {
+            seq,
+            severity,
+            logger_id: logger_id.to_string(),
+            location: location.to_string(),
+            message: message.to_string(),
+        });
+    }
+
+    /// Buffered messages, or only those not yet forwarded when `pending` is set
+    fn entries(&self, pending: bool, clear: bool) -> Vec<LogEntry> {
+        let mut state = self.lock();
+        let since = if pending { state.forwarded } else { 0 };
+        let entries = state
+            .entries
+            .iter()
+            .filter(|entry| entry.seq >= since)
+            .cloned()
+            .collect();
+        if pending {
+            state.forwarded = state.next_seq;
+        }
+        if clear {
+            state.entries.clear();
+        }
+        entries
+    }
+
+    /// Logger handing ORT messages to `buffer`
+    #[cfg(not(target_arch = "wasm32"))]
+    fn logger(buffer: Arc<LogBuffer>) -> ort::logging::LoggerFunction {
+        Box::new(move |level, _category, id, location, message| {
+            buffer.push(LogSeverity::from_ort(level), id, location, message)
+        })
+    }
 
-static ORT_INIT: Once = Once::new();
+    /// Columns of a log data frame, assembled in R
+    fn to_r(entries: &[LogEntry]) -> List {
+        list!(
+            severity = entries.iter().map(|e| e.severity.as_str()).collect::<Vec<_>>(),
+            logger_id = entries.iter().map(|e| e.logger_id.as_str()).collect::<Vec<_>>(),
+            location = entries.iter().map(|e| e.location.as_str()).collect::<Vec<_>>(),
+            message = entries.iter().map(|e| e.message.as_str()).collect::<Vec<_>>()
+        )
+    }
+}
 
 #[derive(Debug, Clone)]
 #[extendr]
@@ -64,6 +280,9 @@ pub enum ChurOnError {
     DataConversion(String),
     Validation(String),
     Provider(String),
+    RuntimeInit(String),
+    /// The session was closed by `RSession::close`
+    Closed,
 }
 
 impl fmt::Display for ChurOnError {
@@ -74,6 +293,8 @@ impl fmt::Display for ChurOnError {
             ChurOnError::DataConversion(msg) => write!(f, "Data conversion error: {}", msg),
             ChurOnError::Validation(msg) => write!(f, "Validation error: {}", msg),
             ChurOnError::Provider(msg) => write!(f, "Provider error: {}", msg),
+            ChurOnError::RuntimeInit(msg) => write!(f, "Runtime initialization error: {}", msg),
+            ChurOnError::Closed => write!(f, "Session is closed"),
         }
     }
 }
@@ -98,15 +319,110 @@ impl From<ChurOnError> for extendr_api::Error {
             ChurOnError::Provider(msg) => {
                 extendr_api::Error::EvalError(format!("Execution provider error: {}", msg).into())
             }
+            ChurOnError::RuntimeInit(msg) => {
+                extendr_api::Error::EvalError(format!("ONNX Runtime initialization failed: {}", msg).into())
+            }
+            // R gives errors with this message the churon_closed_session_error class
+            ChurOnError::Closed => extendr_api::Error::EvalError(
+                "Session is closed; create a new one with onnx_session()".into(),
+            ),
         }
     }
 }
 
 pub type ChurOnResult<T> = std::result::Result<T, ChurOnError>;
 
+/// Input data extracted from R, before conversion into ONNX Runtime values.
+#[derive(Debug, Clone)]
+pub enum InputData {
+    Numeric(ArrayD<f32>),
+    /// Inputs declared with an integer element type, such as token ids, kept
+    /// integral so values above 2^24 survive
+    Integer(ArrayD<i64>),
+    Strings(Vec<String>),
+    Sequence(Vec<InputData>),
+    Map(Vec<(String, f64)>),
+}
+
+impl InputData {
+    /// Shape of a numeric or integer tensor
+    fn shape(&self) -> Option<&[usize]> {
+        match self {
+            InputData::Numeric(tensor) => Some(tensor.shape()),
+            InputData::Integer(tensor) => Some(tensor.shape()),
+            _ => None,
+        }
+    }
+
+    /// Give a numeric or integer tensor a leading batch axis of length one
+    fn insert_batch_axis(&mut self) {
+        match self {
+            InputData::Numeric(tensor) => *tensor = tensor.clone().insert_axis(Axis(0)),
+            InputData::Integer(tensor) => *tensor = tensor.clone().insert_axis(Axis(0)),
+            _ => {}
+        }
+    }
+}
+
+/// Prepared inputs for one run, keyed by input name.
+pub type PreparedInputs = HashMap<String, InputData>;
+
+/// Which end of a sequence receives padding
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PadSide {
+    Left,
+    Right,
+}
+
+impl PadSide {
+    pub fn parse(side: &str) -> ChurOnResult<Self> {
+        match side.to_lowercase().as_str() {
+            "left" => Ok(PadSide::Left),
+            "right" => Ok(PadSide::Right),
+            _ => Err(ChurOnError::Validation(format!(
+                "pad_side must be \"left\" or \"right\", got \"{}\"",
+                side
+            ))),
+        }
+    }
+}
+
+/// How ragged sequence inputs are padded when examples are stacked into a batch
+#[derive(Debug, Clone)]
+pub struct PaddingOptions {
+    pub pad_value: f64,
+    pub side: PadSide,
+    /// Fixed padded length; sequences are truncated to it. `None` pads to the longest
+    /// sequence in each batch.
+    pub max_length: Option<usize>,
+    /// Model input that receives the generated attention mask
+    pub mask_input: Option<String>,
+}
+
+/// Original sequence lengths of a padded batch, used to trim outputs per example
+#[derive(Debug, Clone)]
+pub struct SequenceLayout {
+    pub lengths: Vec<usize>,
+    pub padded_len: usize,
+    pub side: PadSide,
+}
+
+impl SequenceLayout {
+    /// Positions of the real (unpadded) steps of example `i` along the sequence axis
+    fn steps(&self, i: usize) -> Range<usize> {
+        let len = self.lengths[i];
+        match self.side {
+            PadSide::Right => 0..len,
+            PadSide::Left => self.padded_len - len..self.padded_len,
+        }
+    }
+}
+
 #[extendr]
 pub struct RSession {
-    pub session: Session,
+    /// Shared with in-flight asynchronous runs and bindings, which keep it alive
+    /// after R drops the session. `None` once `close` has been called.
+    pub session: Option<Arc<Mutex<Session>>>,
     pub input_names: Vec<String>,
     pub output_names: Vec<String>,
     pub input_shapes: Vec<Vec<i64>>,
@@ -115,12 +431,448 @@ pub struct RSession {
     pub model_path: String,
     input_info_cache: Option<Vec<TensorInfo>>,
     output_info_cache: Option<Vec<TensorInfo>>,
+    /// Messages from this session's ONNX Runtime logger
+    logs: Arc<LogBuffer>,
+    /// Whether ORT's profiler is recording, until `end_profiling` is called
+    profiling: bool,
+    /// Options the session was built with, reused when it is reloaded
+    options: SessionOptions,
+    /// Whether onnxruntime-extensions operators were registered
+    extensions: bool,
+    /// The model file as last loaded, while watching it for changes
+    watch: Option<Option<FileStamp>>,
+}
+
+/// Options applied when a session is built
+#[derive(Debug, Clone, PartialEq)]
+struct SessionOptions {
+    providers: Option<Vec<String>>,
+    log_level: LogSeverity,
+    /// Profiling is enabled when set, writing to a file whose name starts with this
+    profile_prefix: Option<String>,
+    /// Shared libraries of custom operators to register with the session
+    custom_op_libraries: Vec<String>,
+    /// Whether to register onnxruntime-extensions operators, if ORT was built with them
+    extensions: bool,
+    /// Operators with R function kernels, registered by domain
+    r_operators: Vec<ROperator>,
+    /// Sizes pinning symbolic dimensions, by dimension name or ONNX denotation
+    dimension_overrides: Vec<(String, i64)>,
+}
+
+impl Default for SessionOptions {
+    fn default() -> Self {
+        SessionOptions {
+            providers: None,
+            log_level: LogSeverity::Warning,
+            profile_prefix: None,
+            custom_op_libraries: Vec::new(),
+            extensions: false,
+            r_operators: Vec::new(),
+            dimension_overrides: Vec::new(),
+        }
+    }
+}
+
+impl SessionOptions {
+    /// Read the options list `onnx_session()` builds. Missing or NULL entries
+    /// keep their defaults.
+    fn from_list(options: &List) -> ChurOnResult<Self> {
+        let field = |name: &str| {
+            options
+                .iter()
+                .find(|(n, value)| *n == name && !value.is_null())
+                .map(|(_, value)| value)
+        };
+        let invalid = |name: &str, expected: &str| ChurOnError::Validation(format!("{} must be {}", name, expected));
+        let strings = |name: &str| -> ChurOnResult<Option<Vec<String>>> {
+            field(name)
+                .map(|value| {
+                    value
+                        .as_str_vector()
+                        .map(|v| v.iter().map(|s| s.to_string()).collect())
+                        .ok_or_else(|| invalid(name, "a character vector"))
+                })
+                .transpose()
+        };
+
+        let mut parsed = SessionOptions::default();
+        if let Some(level) = field("log_level") {
+            parsed.log_level = LogSeverity::parse(level.as_str().ok_or_else(|| invalid("log_level", "a string"))?)?;
+        }
+        if let Some(prefix) = field("profile_prefix") {
+            parsed.profile_prefix =
+                Some(prefix.as_str().ok_or_else(|| invalid("profile_prefix", "a string"))?.to_string());
+        }
+        parsed.providers = strings("providers")?;
+        parsed.custom_op_libraries = strings("custom_op_libraries")?.unwrap_or_default();
+        if let Some(extensions) = field("extensions") {
+            parsed.extensions = extensions.as_bool().ok_or_else(|| invalid("extensions", "TRUE or FALSE"))?;
+        }
+        if let Some(ops) = field("custom_ops") {
+            let malformed = || invalid("custom_ops", "a list of onnx_custom_op() definitions");
+            parsed.r_operators = ops
+                .as_list()
+                .ok_or_else(malformed)?
+                .values()
+                .map(|op| ROperator::from_list(&List::try_from(op).map_err(|_| malformed())?))
+                .collect::<ChurOnResult<Vec<_>>>()?;
+        }
+        if let Some(sizes) = field("dim_overrides") {
+            let sizes = Doubles::try_from(sizes).map_err(|_| invalid("dim_overrides", "a named numeric vector"))?;
+            parsed.dimension_overrides = Self::dimension_overrides(&sizes)?;
+        }
+        Ok(parsed)
+    }
+
+    /// Read a named vector of dimension sizes such as `c(batch = 1, seq = 128)`
+    fn dimension_overrides(sizes: &Doubles) -> ChurOnResult<Vec<(String, i64)>> {
+        let names: Vec<String> = sizes
+            .names()
+            .map(|names| names.map(|name| name.to_string()).collect())
+            .unwrap_or_default();
+        if names.len() != sizes.len() || names.iter().any(|name| name.is_empty()) {
+            return Err(ChurOnError::Validation(
+                "dim_overrides must be a named vector of sizes, such as c(batch = 1, seq = 128)".to_string(),
+            ));
+        }
+        names
+            .into_iter()
+            .zip(sizes.iter())
+            .map(|(name, size)| {
+                let size = size.inner();
+                if size.is_finite() && size >= 1.0 && size.fract() == 0.0 {
+                    Ok((name, size as i64))
+                } else {
+                    Err(ChurOnError::Validation(format!(
+                        "Dimension '{}' must be overridden with a positive whole number, got {}",
+                        name, size
+                    )))
+                }
+            })
+            .collect()
+    }
+}
+
+/// Modification time and size of a model file, to tell when it has been rewritten.
+/// Contents are not hashed, so a same-size rewrite within the file system's
+/// timestamp resolution goes unnoticed.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct FileStamp {
+    modified: Option<SystemTime>,
+    size: u64,
+}
+
+impl FileStamp {
+    fn of(metadata: &std::fs::Metadata) -> Self {
+        FileStamp {
+            modified: metadata.modified().ok(),
+            size: metadata.len(),
+        }
+    }
+
+    /// Stamp of the file at `path`, or `None` when it cannot be read
+    fn read(path: &str) -> Option<Self> {
+        std::fs::metadata(path).ok().map(|metadata| Self::of(&metadata))
+    }
+}
+
+/// Sessions handed out by `RSession::from_path(cache = TRUE)`
+static SESSION_CACHE: Mutex<SessionCache> = Mutex::new(SessionCache {
+    entries: Vec::new(),
+    max_bytes: None,
+});
+
+/// Process-wide cache of sessions keyed by model file and options, ordered from
+/// least to most recently used. Memory use is estimated by model file size.
+struct SessionCache {
+    entries: Vec<CacheEntry>,
+    max_bytes: Option<u64>,
+}
+
+struct CacheEntry {
+    /// Canonical model path
+    path: String,
+    /// The file the session was built from
+    stamp: FileStamp,
+    options: SessionOptions,
+    session: RSession,
+    hits: u64,
+}
+
+impl SessionCache {
+    fn lock() -> MutexGuard<'static, SessionCache> {
+        // Entries are only added or removed whole, so a poisoned cache is consistent
+        SESSION_CACHE.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// A session sharing the cached one for `path` and `options`, building and
+    /// caching it first when the file is new or has changed since
+    fn get_or_create(path: &str, options: &SessionOptions) -> extendr_api::Result<RSession> {
+        let canonical = std::fs::canonicalize(path)
+            .map_err(|e| ChurOnError::ModelLoad(format!("Cannot read model file '{}': {}", path, e)))?;
+        let metadata = std::fs::metadata(&canonical)
+            .map_err(|e| ChurOnError::ModelLoad(format!("Cannot read model file '{}': {}", path, e)))?;
+        let canonical = canonical.to_string_lossy().into_owned();
+        let stamp = FileStamp::of(&metadata);
+
+        {
+            let mut cache = Self::lock();
+            if let Some(index) = cache
+                .entries
+                .iter()
+                .position(|entry| entry.path == canonical && entry.options == *options)
+            {
+                let mut entry = cache.entries.remove(index);
+                if entry.stamp == stamp {
+                    entry.hits += 1;
+                    let session = entry.session.share();
+                    cache.entries.push(entry);
+                    return Ok(session);
+                }
+            }
+        }
+
+        // Built without holding the lock; a racing build of the same model just
+        // replaces this entry
+        let session = RSession::from_path_with_options(&canonical, options)?;
+        let shared = session.share();
+        let mut cache = Self::lock();
+        cache
+            .entries
+            .retain(|entry| !(entry.path == canonical && entry.options == *options));
+        cache.entries.push(CacheEntry {
+            path: canonical,
+            stamp,
+            options: options.clone(),
+            session,
+            hits: 0,
+        });
+        cache.enforce_limit();
+        Ok(shared)
+    }
+
+    /// Model file sizes of the cached sessions, standing in for their memory use
+    fn total_bytes(&self) -> u64 {
+        self.entries.iter().map(|entry| entry.stamp.size).sum()
+    }
+
+    /// Evict least recently used sessions until the cache fits `max_bytes`
+    fn enforce_limit(&mut self) {
+        if let Some(max_bytes) = self.max_bytes {
+            while self.total_bytes() > max_bytes && !self.entries.is_empty() {
+                self.entries.remove(0);
+            }
+        }
+    }
+
+    /// Evict the entries for `path`, or every entry. Returns how many were evicted.
+    fn evict(&mut self, path: Option<&str>) -> usize {
+        let before = self.entries.len();
+        match path {
+            Some(path) => {
+                let canonical = std::fs::canonicalize(path)
+                    .map(|p| p.to_string_lossy().into_owned())
+                    .unwrap_or_else(|_| path.to_string());
+                self.entries.retain(|entry| entry.path != canonical);
+            }
+            None => self.entries.clear(),
+        }
+        before - self.entries.len()
+    }
+
+    /// Drop the entry holding `session`, if any
+    fn forget(&mut self, session: &Arc<Mutex<Session>>) {
+        self.entries.retain(|entry| {
+            !entry
+                .session
+                .session
+                .as_ref()
+                .is_some_and(|cached| Arc::ptr_eq(cached, session))
+        });
+    }
+
+    /// Cached sessions as data frame columns, least recently used first. `in_use`
+    /// counts the R sessions, asynchronous runs and bindings sharing each one.
+    fn to_r(&self) -> List {
+        let in_use = |entry: &CacheEntry| {
+            entry.session.session.as_ref().map_or(0, |s| Arc::strong_count(s) as i32 - 1)
+        };
+        let options = |column: fn(&SessionOptions) -> String| {
+            self.entries.iter().map(|e| column(&e.options)).collect::<Vec<_>>()
+        };
+        list!(
+            path = self.entries.iter().map(|e| e.path.clone()).collect::<Vec<_>>(),
+            log_level = self.entries.iter().map(|e| e.options.log_level.as_str()).collect::<Vec<_>>(),
+            providers = options(|o| o.providers.as_deref().unwrap_or_default().join(", ")),
+            custom_op_libraries = options(|o| o.custom_op_libraries.join(", ")),
+            extensions = self.entries.iter().map(|e| e.options.extensions).collect::<Vec<_>>(),
+            dim_overrides = options(|o| {
+                o.dimension_overrides
+                    .iter()
+                    .map(|(name, size)| format!("{}={}", name, size))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            }),
+            size_bytes = self.entries.iter().map(|e| e.stamp.size as f64).collect::<Vec<_>>(),
+            hits = self.entries.iter().map(|e| e.hits as f64).collect::<Vec<_>>(),
+            in_use = self.entries.iter().map(in_use).collect::<Vec<_>>()
+        )
+    }
 }
 
 #[extendr]
 impl RSession {
-    pub fn from_path(path: &str) -> extendr_api::Result<Self> {
-        Self::from_path_with_providers_internal(path, None)
+    /// With `cache`, sessions for the same unchanged file and options share one
+    /// ORT session; see `SessionCache`. Profiling sessions and sessions with R
+    /// custom operators are never cached.
+    pub fn from_path(
+        path: &str,
+        #[default = "NULL"] options: Nullable<List>,
+        #[default = "FALSE"] cache: bool,
+    ) -> extendr_api::Result<Self> {
+        let options = match options.into_option() {
+            Some(options) => SessionOptions::from_list(&options)?,
+            None => SessionOptions::default(),
+        };
+        if cache && options.profile_prefix.is_none() && options.r_operators.is_empty() {
+            SessionCache::get_or_create(path, &options)
+        } else {
+            Self::from_path_with_options(path, &options)
+        }
+    }
+
+    /// Buffered log messages as data frame columns. `pending` limits them to
+    /// messages not returned by an earlier pending call; `clear` empties the buffer.
+    fn get_logs(
+        &self,
+        #[default = "FALSE"] pending: bool,
+        #[default = "FALSE"] clear: bool,
+    ) -> extendr_api::Result<List> {
+        self.shared_session()?;
+        Ok(LogBuffer::to_r(&self.logs.entries(pending, clear)))
+    }
+
+    fn get_log_level(&self) -> extendr_api::Result<String> {
+        self.shared_session()?;
+        Ok(self.logs.min_severity().as_str().to_string())
+    }
+
+    /// Drop this object's hold on the ORT session, freeing the model unless an
+    /// asynchronous run or binding still uses it. Every later method call fails.
+    fn close(&mut self) {
+        if let Some(session) = self.session.take() {
+            SessionCache::lock().forget(&session);
+        }
+        self.input_info_cache = None;
+        self.output_info_cache = None;
+    }
+
+    fn is_valid(&self) -> bool {
+        self.session.is_some()
+    }
+
+    /// Start or stop watching the model file for `reload_if_changed`
+    fn set_watch(&mut self, enabled: bool) -> extendr_api::Result<()> {
+        self.shared_session()?;
+        self.watch = enabled.then(|| FileStamp::read(&self.model_path));
+        Ok(())
+    }
+
+    fn is_watching(&self) -> bool {
+        self.watch.is_some()
+    }
+
+    /// Registered custom operator libraries, whether onnxruntime-extensions is
+    /// enabled, the `domain:name` of operators with R kernels, and the operator
+    /// domains registered on the session. The domains of a library's operators
+    /// cannot be queried, so libraries are only listed by path
+    fn get_custom_ops(&self) -> extendr_api::Result<List> {
+        self.shared_session()?;
+        // Domains of operator libraries are only known to the libraries themselves
+        let mut domains = vec![CHURON_DOMAIN.to_string()];
+        for op in &self.options.r_operators {
+            if !domains.contains(&op.domain) {
+                domains.push(op.domain.clone());
+            }
+        }
+        if self.extensions {
+            domains.push(EXTENSIONS_DOMAIN.to_string());
+        }
+        Ok(list!(
+            libraries = self.options.custom_op_libraries.clone(),
+            extensions = self.extensions,
+            r_operators = self.options.r_operators.iter().map(ROperator::qualified_name).collect::<Vec<_>>(),
+            domains = domains
+        ))
+    }
+
+    /// When watching and the model file has changed since it was last loaded,
+    /// build a new session from it and swap it in. A file that fails to load
+    /// leaves the current session in place and is not retried until it changes
+    /// again. Returns `reloaded` and the load `error`, if any.
+    fn reload_if_changed(&mut self) -> extendr_api::Result<List> {
+        self.shared_session()?;
+        let Some(loaded) = self.watch else {
+            return Ok(list!(reloaded = false, error = NULL));
+        };
+        let current = FileStamp::read(&self.model_path);
+        if current == loaded {
+            return Ok(list!(reloaded = false, error = NULL));
+        }
+        self.watch = Some(current);
+        if current.is_none() {
+            return Ok(list!(
+                reloaded = false,
+                error = format!("Model file not found: {}", self.model_path)
+            ));
+        }
+
+        // The new session logs into the same buffer, and keeps profiling only if
+        // the old one still was
+        let mut options = self.options.clone();
+        if !self.profiling {
+            options.profile_prefix = None;
+        }
+        match Self::from_path_with_logs(&self.model_path, &options, Arc::clone(&self.logs)) {
+            Ok(fresh) => {
+                // Runs already started keep the previous session until they finish
+                self.session = fresh.session;
+                self.input_names = fresh.input_names;
+                self.output_names = fresh.output_names;
+                self.input_shapes = fresh.input_shapes;
+                self.output_shapes = fresh.output_shapes;
+                self.input_info_cache = None;
+                self.output_info_cache = None;
+                self.extensions = fresh.extensions;
+                Ok(list!(reloaded = true, error = NULL))
+            }
+            Err(e) => Ok(list!(reloaded = false, error = e.to_string())),
+        }
+    }
+
+    /// Stop ORT's profiler and return the path of the Chrome trace JSON it wrote
+    fn end_profiling(&mut self) -> extendr_api::Result<String> {
+        #[cfg(target_arch = "wasm32")]
+        {
+            Err(extendr_api::Error::EvalError("ONNX Runtime is not supported on WASM".into()))
+        }
+
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+        if !self.profiling {
+            return Err(ChurOnError::Validation(
+                "Profiling is not enabled for this session; create it with profile = TRUE".to_string(),
+            )
+            .into());
+        }
+        let path = self
+            .lock_session()?
+            .end_profiling()
+            .map_err(|e| ChurOnError::Inference(format!("Failed to end profiling: {}", e)))?;
+        self.profiling = false;
+        Ok(path)
+        }
     }
 
     pub fn get_input_info(&mut self) -> extendr_api::Result<List> {
@@ -131,12 +883,14 @@ impl RSession {
 
         #[cfg(not(target_arch = "wasm32"))]
         {
+        self.shared_session()?;
         if let Some(ref cached_info) = self.input_info_cache {
             return Ok(List::from_values(cached_info.clone()));
         }
 
-        let inputs: Vec<_> = self.session.inputs.iter().collect();
-        let tensor_infos: Vec<TensorInfo> = inputs
+        let session = self.lock_session()?;
+        let tensor_infos: Vec<TensorInfo> = session
+            .inputs
             .iter()
             .enumerate()
             .map(|(i, input)| {
@@ -149,6 +903,7 @@ impl RSession {
                 )
             })
             .collect();
+        drop(session);
 
         self.input_info_cache = Some(tensor_infos.clone());
         Ok(List::from_values(tensor_infos))
@@ -163,12 +918,14 @@ impl RSession {
 
         #[cfg(not(target_arch = "wasm32"))]
         {
+        self.shared_session()?;
         if let Some(ref cached_info) = self.output_info_cache {
             return Ok(List::from_values(cached_info.clone()));
         }
 
-        let outputs: Vec<_> = self.session.outputs.iter().collect();
-        let tensor_infos: Vec<TensorInfo> = outputs
+        let session = self.lock_session()?;
+        let tensor_infos: Vec<TensorInfo> = session
+            .outputs
             .iter()
             .enumerate()
             .map(|(i, output)| {
@@ -181,76 +938,346 @@ impl RSession {
                 )
             })
             .collect();
+        drop(session);
 
         self.output_info_cache = Some(tensor_infos.clone());
         Ok(List::from_values(tensor_infos))
         }
     }
 
-    pub fn get_providers(&self) -> Vec<String> {
-        self.providers.clone()
+    pub fn get_providers(&self) -> extendr_api::Result<Vec<String>> {
+        self.shared_session()?;
+        Ok(self.providers.clone())
     }
 
-    pub fn get_model_path(&self) -> String {
-        self.model_path.clone()
+    pub fn get_model_path(&self) -> extendr_api::Result<String> {
+        self.shared_session()?;
+        Ok(self.model_path.clone())
     }
 
-    fn run(&mut self, inputs: List) -> extendr_api::Result<List> {
+    /// Run the model once. With `stats`, the result carries `timing_ms` and `bytes`
+    /// attributes describing where the call spent its time and memory. With
+    /// `lazy_outputs`, float32 tensor outputs stay in ORT's buffer; see `LazyF32Output`.
+    /// With `output_precision = "float32"` they are returned without widening.
+    fn run(
+        &mut self,
+        inputs: List,
+        #[default = "FALSE"] auto_batch: bool,
+        #[default = "FALSE"] stats: bool,
+        #[default = "FALSE"] lazy_outputs: bool,
+        #[default = "'double'"] output_precision: &str,
+    ) -> extendr_api::Result<List> {
         #[cfg(target_arch = "wasm32")]
         {
+            let _ = output_precision;
             // Return empty list for WASM - ort crate not available on wasm
             Ok(List::from_values(Vec::<Robj>::new()))
         }
 
         #[cfg(not(target_arch = "wasm32"))]
         {
+        let precision = OutputPrecision::parse(output_precision)?;
         self.validate_session()?;
         self.validate_inputs(&inputs)?;
-        let input_data = self.prepare_input_tensors(inputs)?;
-        let ort_inputs = self.convert_to_ort_values(input_data)?;
+        let mut run_stats = RunStats::default();
+        let started = Instant::now();
+        let input_objs = Self::named_inputs(&inputs);
+        run_stats.prepare_input_tensors = started.elapsed();
+
+        // Numeric inputs go straight from R's memory to ORT; see borrow_input_values
+        let started = Instant::now();
+        let (ort_inputs, batched) = self.borrow_input_values(&input_objs, auto_batch)?;
+        run_stats.convert_to_ort_values = started.elapsed();
+        if stats {
+            run_stats.input_bytes = ort_inputs.iter().map(|(_, v)| DataConverter::value_bytes(v.dtype())).sum();
+        }
 
         // Clone output names before the mutable borrow scope
         let output_names = self.output_names.clone();
 
-        // Use a block to limit the mutable borrow scope
-        let outputs = {
-            self.session
-                .run(ort_inputs)
-                .map_err(|e| ChurOnError::Inference(format!("Inference execution failed: {}", e)))?
-        };
-
-        Self::extract_outputs(outputs, &output_names)
+        let mut session = self.lock_session()?;
+        let started = Instant::now();
+        let mut outputs = session
+            .run(ort_inputs)
+            .map_err(|e| ChurOnError::Inference(format!("Inference execution failed: {}", e)))?;
+        run_stats.session_run = started.elapsed();
+        if stats {
+            run_stats.output_bytes = output_names
+                .iter()
+                .filter_map(|name| outputs.get(name))
+                .map(|v| DataConverter::value_bytes(v.dtype()))
+                .sum();
         }
-    }
-}
 
-impl RSession {
-    fn validate_session(&self) -> ChurOnResult<()> {
-        if self.input_names.is_empty() {
-            return Err(ChurOnError::Validation(
-                "Session has no input tensors defined".to_string(),
-            ));
-        }
-        if self.output_names.is_empty() {
-            return Err(ChurOnError::Validation(
-                "Session has no output tensors defined".to_string(),
-            ));
+        let started = Instant::now();
+        let mut result = if lazy_outputs || precision == OutputPrecision::Float32 {
+            let values = output_names
+                .iter()
+                .map(|name| {
+                    outputs
+                        .remove(name)
+                        .ok_or_else(|| ChurOnError::Inference(format!("Output '{}' not found", name)))
+                })
+                .collect::<ChurOnResult<Vec<_>>>()?;
+            List::try_from(DataConverter::owned_values_to_r_list(
+                &output_names,
+                values,
+                batched,
+                lazy_outputs,
+                precision,
+            )?)?
+        } else {
+            Self::extract_outputs(outputs, &output_names, batched)?
+        };
+        run_stats.extract_outputs = started.elapsed();
+        if stats {
+            run_stats.attach(&mut result)?;
         }
-        if self.model_path.is_empty() {
-            return Err(ChurOnError::Validation(
-                "Session has no model path defined".to_string(),
-            ));
+        Ok(result)
         }
-        Ok(())
     }
 
-    fn validate_inputs(&self, inputs: &List) -> ChurOnResult<()> {
-        if inputs.len() == 0 {
+    /// Run the model `warmup + iterations` times on the same inputs, timing input
+    /// conversion, the ORT run and output conversion of each measured iteration
+    fn benchmark(
+        &mut self,
+        inputs: List,
+        #[default = "10L"] warmup: i32,
+        #[default = "100L"] iterations: i32,
+        #[default = "FALSE"] copy_inputs: bool,
+    ) -> extendr_api::Result<List> {
+        #[cfg(target_arch = "wasm32")]
+        {
+            let _ = (inputs, warmup, iterations, copy_inputs);
+            Err(extendr_api::Error::EvalError("ONNX Runtime is not supported on WASM".into()))
+        }
+
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+        if warmup < 0 || iterations < 1 {
             return Err(ChurOnError::Validation(
-                "No input data provided".to_string(),
-            ));
+                "warmup must be non-negative and iterations positive".to_string(),
+            )
+            .into());
         }
-        let input_names = inputs.names().unwrap_or_default();
+        self.validate_session()?;
+        self.validate_inputs(&inputs)?;
+        let output_names = self.output_names.clone();
+        let input_objs = Self::named_inputs(&inputs);
+
+        let mut timings = BenchmarkTimings::default();
+        for i in 0..(warmup + iterations) {
+            if i == warmup {
+                timings = BenchmarkTimings::default();
+            }
+            let t0 = Instant::now();
+            // Owned copies are what asynchronous and pooled runs pay for
+            let ort_inputs: Vec<(String, SessionInputValue<'_>)> = if copy_inputs {
+                let input_data = self.prepare_input_tensors(inputs.clone())?;
+                self.convert_to_ort_values(&input_data)?
+                    .into_iter()
+                    .map(|(name, value)| (name, value.into()))
+                    .collect()
+            } else {
+                self.borrow_input_values(&input_objs, false)?.0
+            };
+            let t1 = Instant::now();
+            let outputs = {
+                let mut session = self.lock_session()?;
+                Self::take_outputs(&mut session, ort_inputs, &output_names)?
+            };
+            let t2 = Instant::now();
+            DataConverter::values_to_r_list(&output_names, &outputs, false)?;
+            let t3 = Instant::now();
+            timings.record(t1 - t0, t2 - t1, t3 - t2);
+        }
+        Ok(timings.summary(warmup))
+        }
+    }
+
+    /// Bind `inputs` and outputs sized from a first run, for repeated runs on
+    /// inputs of the same shape
+    fn create_binding(&mut self, inputs: List) -> extendr_api::Result<RBinding> {
+        #[cfg(target_arch = "wasm32")]
+        {
+            let _ = inputs;
+            Err(extendr_api::Error::EvalError("ONNX Runtime is not supported on WASM".into()))
+        }
+
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+        self.validate_session()?;
+        self.validate_inputs(&inputs)?;
+        Ok(RBinding::create(self, &inputs)?)
+        }
+    }
+
+    fn run_async(&mut self, inputs: List, #[default = "FALSE"] auto_batch: bool) -> extendr_api::Result<RInferenceHandle> {
+        #[cfg(target_arch = "wasm32")]
+        {
+            Err(extendr_api::Error::EvalError("ONNX Runtime is not supported on WASM".into()))
+        }
+
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+        // Inputs are converted here, since R objects cannot be read from the worker
+        self.validate_session()?;
+        self.validate_inputs(&inputs)?;
+        let batched = auto_batch && self.batch_axis_missing(&Self::named_inputs(&inputs))?;
+        let mut input_data = self.prepare_input_tensors(inputs)?;
+        if batched {
+            self.add_batch_axis(&mut input_data);
+        }
+        let ort_inputs = self.convert_to_ort_values(&input_data)?;
+
+        Ok(RInferenceHandle::spawn(
+            Arc::clone(self.shared_session()?),
+            ort_inputs,
+            self.output_names.clone(),
+            batched,
+        ))
+        }
+    }
+
+    fn run_batch(&mut self, inputs: List, #[default = "32L"] batch_size: i32) -> extendr_api::Result<List> {
+        #[cfg(target_arch = "wasm32")]
+        {
+            // Return empty list for WASM - ort crate not available on wasm
+            Ok(List::from_values(Vec::<Robj>::new()))
+        }
+
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+        self.validate_session()?;
+        if batch_size < 1 {
+            return Err(ChurOnError::Validation("batch_size must be a positive integer".to_string()).into());
+        }
+
+        let items: Vec<Robj> = inputs.values().collect();
+        let mut results: Vec<Robj> = vec![Robj::from(()); items.len()];
+
+        for (chunk_index, chunk) in items.chunks(batch_size as usize).enumerate() {
+            let offset = chunk_index * batch_size as usize;
+
+            // Prepare every example on its own so one bad item doesn't sink the chunk
+            let mut prepared = Vec::new();
+            for (i, item) in chunk.iter().enumerate() {
+                match self.prepare_batch_item(item) {
+                    Ok(data) => prepared.push((offset + i, data)),
+                    Err(e) => results[offset + i] = DataConverter::batch_error(&e.to_string()),
+                }
+            }
+
+            for (indices, stacked) in Self::stack_batch(prepared, self.has_dynamic_batch_axis()) {
+                let outcome = stacked.and_then(|stacked| self.run_stacked(stacked, indices.len(), None));
+                Self::store_batch_outcome(&mut results, indices, outcome);
+            }
+        }
+
+        Self::batch_result_list(&inputs, results)
+        }
+    }
+
+    fn run_padded_batch(
+        &mut self,
+        inputs: List,
+        #[default = "32L"] batch_size: i32,
+        #[default = "0"] pad_value: f64,
+        #[default = "'right'"] pad_side: &str,
+        #[default = "NULL"] max_length: Nullable<i32>,
+        #[default = "NULL"] mask_input: Nullable<String>,
+    ) -> extendr_api::Result<List> {
+        #[cfg(target_arch = "wasm32")]
+        {
+            // Return empty list for WASM - ort crate not available on wasm
+            Ok(List::from_values(Vec::<Robj>::new()))
+        }
+
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+        self.validate_session()?;
+        if batch_size < 1 {
+            return Err(ChurOnError::Validation("batch_size must be a positive integer".to_string()).into());
+        }
+        let padding = self.padding_options(pad_value, pad_side, max_length.into_option(), mask_input.into_option())?;
+
+        let items: Vec<Robj> = inputs.values().collect();
+        let mut results: Vec<Robj> = vec![Robj::from(()); items.len()];
+
+        for (chunk_index, chunk) in items.chunks(batch_size as usize).enumerate() {
+            let offset = chunk_index * batch_size as usize;
+
+            let mut prepared = Vec::new();
+            for (i, item) in chunk.iter().enumerate() {
+                match self.prepare_ragged_item(item, &padding) {
+                    Ok(data) => prepared.push((offset + i, data)),
+                    Err(e) => results[offset + i] = DataConverter::batch_error(&e.to_string()),
+                }
+            }
+
+            // Every sequence in the chunk is padded to the same length before stacking
+            let padded_len = padding
+                .max_length
+                .unwrap_or_else(|| prepared.iter().map(|(_, (_, len))| *len).max().unwrap_or(0));
+            let mut lengths = HashMap::new();
+            let padded: Vec<(usize, PreparedInputs)> = prepared
+                .into_iter()
+                .map(|(index, (data, len))| {
+                    lengths.insert(index, len);
+                    (index, Self::pad_item(data, len, padded_len, &padding))
+                })
+                .collect();
+
+            for (indices, stacked) in Self::stack_batch(padded, self.has_dynamic_batch_axis()) {
+                let layout = SequenceLayout {
+                    lengths: indices.iter().map(|index| lengths[index]).collect(),
+                    padded_len,
+                    side: padding.side,
+                };
+                let outcome = stacked.and_then(|stacked| self.run_stacked(stacked, indices.len(), Some(&layout)));
+                Self::store_batch_outcome(&mut results, indices, outcome);
+            }
+        }
+
+        Self::batch_result_list(&inputs, results)
+        }
+    }
+}
+
+impl RSession {
+    fn validate_session(&self) -> ChurOnResult<()> {
+        self.shared_session()?;
+        if self.input_names.is_empty() {
+            return Err(ChurOnError::Validation(
+                "Session has no input tensors defined".to_string(),
+            ));
+        }
+        if self.output_names.is_empty() {
+            return Err(ChurOnError::Validation(
+                "Session has no output tensors defined".to_string(),
+            ));
+        }
+        if self.model_path.is_empty() {
+            return Err(ChurOnError::Validation(
+                "Session has no model path defined".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    fn validate_inputs(&self, inputs: &List) -> ChurOnResult<()> {
+        self.validate_inputs_except(inputs, None)
+    }
+
+    /// Validate input names, allowing `generated` to be missing because it is built
+    /// in Rust rather than provided from R
+    fn validate_inputs_except(&self, inputs: &List, generated: Option<&str>) -> ChurOnResult<()> {
+        if inputs.is_empty() {
+            return Err(ChurOnError::Validation(
+                "No input data provided".to_string(),
+            ));
+        }
+        let input_names = inputs.names().unwrap_or_default();
         if input_names.len() == 0 {
             return Err(ChurOnError::Validation(
                 "Input data must be a named list".to_string(),
@@ -258,6 +1285,15 @@ impl RSession {
         }
         let provided_input_names: Vec<String> = input_names.map(|name| name.to_string()).collect();
         for required_input in &self.input_names {
+            if Some(required_input.as_str()) == generated {
+                if provided_input_names.contains(required_input) {
+                    return Err(ChurOnError::Validation(format!(
+                        "Input '{}' is generated from the padding and must not be provided",
+                        required_input
+                    )));
+                }
+                continue;
+            }
             if !provided_input_names.contains(required_input) {
                 return Err(ChurOnError::Validation(format!(
                     "Required input '{}' not provided",
@@ -276,12 +1312,8 @@ impl RSession {
         Ok(())
     }
 
-    fn prepare_input_tensors(
-        &self,
-        inputs: List,
-    ) -> ChurOnResult<(HashMap<String, ArrayD<f32>>, HashMap<String, Vec<String>>)> {
-        let mut numeric_tensors = HashMap::new();
-        let mut string_tensors = HashMap::new();
+    fn prepare_input_tensors(&self, inputs: List) -> ChurOnResult<PreparedInputs> {
+        let mut prepared = HashMap::new();
         let input_names = inputs.names().unwrap_or_default();
 
         for (i, input_name) in input_names.enumerate() {
@@ -294,16 +1326,26 @@ impl RSession {
                 ))
             })?;
 
+            // Sequence and map inputs are built from R lists and named vectors
+            #[cfg(not(target_arch = "wasm32"))]
+            if let Some(structured) = self.prepare_structured_input(input_name_str, &input_robj)? {
+                prepared.insert(input_name_str.to_string(), structured);
+                continue;
+            }
+
             // Check if input is numeric or string
             if input_robj.is_string() || input_robj.is_char() {
                 // Extract string data
                 if let Some(strs) = input_robj.as_str_vector() {
-                    string_tensors.insert(
+                    prepared.insert(
                         input_name_str.to_string(),
-                        strs.iter().map(|s| s.to_string()).collect(),
+                        InputData::Strings(strs.iter().map(|s| s.to_string()).collect()),
                     );
                 } else if let Some(single_str) = input_robj.as_str() {
-                    string_tensors.insert(input_name_str.to_string(), vec![single_str.to_string()]);
+                    prepared.insert(
+                        input_name_str.to_string(),
+                        InputData::Strings(vec![single_str.to_string()]),
+                    );
                 } else {
                     return Err(ChurOnError::DataConversion(format!(
                         "Failed to convert input '{}' to string data",
@@ -327,200 +1369,711 @@ impl RSession {
                     .collect();
 
                 // Extract numeric data from R object (handles matrices, arrays, vectors)
-                let tensor = DataConverter::r_obj_to_ndarray_f32(&input_robj, &shape_usize)?;
-                numeric_tensors.insert(input_name_str.to_string(), tensor);
+                let input = if self.is_integer_input(input_name_str)? {
+                    InputData::Integer(DataConverter::r_obj_to_ndarray_i64(&input_robj, &shape_usize)?)
+                } else {
+                    InputData::Numeric(DataConverter::r_obj_to_ndarray_f32(&input_robj, &shape_usize)?)
+                };
+                prepared.insert(input_name_str.to_string(), input);
             }
         }
-        Ok((numeric_tensors, string_tensors))
+        Ok(prepared)
     }
 
-    fn convert_to_ort_values(
-        &self,
-        input_data: (HashMap<String, ArrayD<f32>>, HashMap<String, Vec<String>>),
-    ) -> ChurOnResult<HashMap<String, Value>> {
-        #[cfg(target_arch = "wasm32")]
-        {
-            // Return empty hashmap for WASM - ort crate not available on wasm
-            Ok(HashMap::new())
+    /// Validate and prepare a single example of a batch, giving every numeric input a
+    /// leading batch axis of length one
+    fn prepare_batch_item(&self, item: &Robj) -> ChurOnResult<PreparedInputs> {
+        let item_inputs = item.as_list().ok_or_else(|| {
+            ChurOnError::Validation("Each batch item must be a named list of inputs".to_string())
+        })?;
+        self.validate_inputs(&item_inputs)?;
+        let mut data = self.prepare_input_tensors(item_inputs)?;
+        self.add_batch_axis(&mut data);
+        Self::check_single_example(&data)?;
+        Ok(data)
+    }
+
+    /// Validate and prepare one independent run for a session pool. Returns whether a
+    /// batch axis was added, so it can be dropped again from the outputs.
+    fn prepare_pool_item(&self, item: &Robj, auto_batch: bool) -> ChurOnResult<(PreparedInputs, bool)> {
+        let item_inputs = item.as_list().ok_or_else(|| {
+            ChurOnError::Validation("Each item must be a named list of inputs".to_string())
+        })?;
+        self.validate_inputs(&item_inputs)?;
+        let batched = auto_batch && self.batch_axis_missing(&Self::named_inputs(&item_inputs))?;
+        let mut data = self.prepare_input_tensors(item_inputs)?;
+        if batched {
+            self.add_batch_axis(&mut data);
         }
+        Ok((data, batched))
+    }
 
-        #[cfg(not(target_arch = "wasm32"))]
-        {
-        let (numeric_tensors, string_tensors) = input_data;
-        let mut values: HashMap<String, Value> = HashMap::new();
+    /// Validate and prepare a single example of a padded batch. Rank one numeric inputs
+    /// are the example's sequences: they must share a length and are truncated to
+    /// `max_length`, but padding waits until the whole chunk is known. Other inputs
+    /// are prepared as in `prepare_batch_item`. Returns the sequence length.
+    fn prepare_ragged_item(
+        &self,
+        item: &Robj,
+        padding: &PaddingOptions,
+    ) -> ChurOnResult<(PreparedInputs, usize)> {
+        let item_inputs = item.as_list().ok_or_else(|| {
+            ChurOnError::Validation("Each batch item must be a named list of inputs".to_string())
+        })?;
+        self.validate_inputs_except(&item_inputs, padding.mask_input.as_deref())?;
+        let data = self.prepare_input_tensors(item_inputs)?;
 
-        // Handle numeric tensors
-        for input_name in &self.input_names {
-            if let Some(tensor) = numeric_tensors.get(input_name) {
-                let shape: Vec<usize> = tensor.shape().iter().map(|&d| d as usize).collect();
-                let data: Vec<f32> = tensor.iter().cloned().collect();
-                let ort_tensor = Tensor::from_array((shape, data)).map_err(|e| {
-                    ChurOnError::DataConversion(format!(
-                        "Failed to create tensor for input '{}': {}",
-                        input_name, e
-                    ))
-                })?;
-                let value: Value = ort_tensor.into();
-                values.insert(input_name.clone(), value);
+        let (mut sequences, mut data): (PreparedInputs, PreparedInputs) = data
+            .into_iter()
+            .partition(|(_, input)| matches!(input.shape(), Some(shape) if shape.len() == 1));
+
+        let mut length = None;
+        for (input_name, input) in sequences.iter_mut() {
+            let Some(&[input_len]) = input.shape() else {
+                continue;
+            };
+            match length {
+                None => length = Some(input_len),
+                Some(len) if len != input_len => {
+                    return Err(ChurOnError::Validation(format!(
+                        "Sequence inputs of a batch item must share a length, but '{}' has length {} instead of {}",
+                        input_name, input_len, len
+                    )));
+                }
+                _ => {}
+            }
+            if let Some(max_length) = padding.max_length {
+                let kept = Slice::from(..max_length.min(input_len));
+                match input {
+                    InputData::Numeric(tensor) => tensor.slice_axis_inplace(Axis(0), kept),
+                    InputData::Integer(tensor) => tensor.slice_axis_inplace(Axis(0), kept),
+                    _ => {}
+                }
             }
         }
+        let length = match length {
+            Some(0) => {
+                return Err(ChurOnError::Validation(
+                    "Batch item sequences must not be empty".to_string(),
+                ))
+            }
+            Some(len) => padding.max_length.map_or(len, |max_length| len.min(max_length)),
+            None => {
+                return Err(ChurOnError::Validation(
+                    "Batch item has no sequence input to pad; give sequences as plain vectors".to_string(),
+                ))
+            }
+        };
 
-        // Handle string tensors using Tensor::from_string_array
-        for (input_name, string_data) in &string_tensors {
-            let shape = [string_data.len()];
-            // Create owned string array to avoid lifetime issues
-            let string_array: Vec<String> = string_data.iter().map(|s| s.to_string()).collect();
-            let ort_tensor =
-                Tensor::from_string_array((shape, string_array.as_slice())).map_err(|e| {
-                    ChurOnError::DataConversion(format!(
-                        "Failed to create string tensor for input '{}': {}",
-                        input_name, e
-                    ))
-                })?;
-            let value: Value = ort_tensor.into();
-            values.insert(input_name.clone(), value);
-        }
+        self.add_batch_axis(&mut data);
+        Self::check_single_example(&data)?;
+        data.extend(sequences);
+        Ok((data, length))
+    }
 
-        Ok(values)
+    /// Pad every sequence of a prepared example to shape `[1, padded_len]` and add the
+    /// attention mask input when one is configured
+    fn pad_item(
+        mut data: PreparedInputs,
+        len: usize,
+        padded_len: usize,
+        padding: &PaddingOptions,
+    ) -> PreparedInputs {
+        for input in data.values_mut() {
+            match input {
+                InputData::Numeric(tensor) if tensor.ndim() == 1 => {
+                    *tensor = Self::pad_sequence(tensor.view(), padded_len, padding.pad_value as f32, padding.side);
+                }
+                InputData::Integer(tensor) if tensor.ndim() == 1 => {
+                    *tensor = Self::pad_sequence(tensor.view(), padded_len, padding.pad_value as i64, padding.side);
+                }
+                _ => {}
+            }
+        }
+        if let Some(mask_input) = &padding.mask_input {
+            let ones = ArrayD::<f32>::ones(IxDyn(&[len]));
+            let mask = Self::pad_sequence(ones.view(), padded_len, 0.0f32, padding.side);
+            data.insert(mask_input.clone(), InputData::Numeric(mask));
         }
+        data
     }
 
-    fn extract_outputs(
-        outputs: SessionOutputs,
-        output_names: &[String],
-    ) -> extendr_api::Result<List> {
-        #[cfg(target_arch = "wasm32")]
-        {
-            // On wasm, ort is not available, so return empty list
-            Ok(List::from_values(Vec::<Robj>::new()))
+    /// Pad a rank one sequence with `pad_value` on the given side, adding a leading
+    /// batch axis
+    fn pad_sequence<T: Copy>(
+        sequence: ArrayViewD<T>,
+        padded_len: usize,
+        pad_value: T,
+        side: PadSide,
+    ) -> ArrayD<T> {
+        let start = match side {
+            PadSide::Right => 0,
+            PadSide::Left => padded_len - sequence.len(),
+        };
+        let mut padded = vec![pad_value; padded_len];
+        for (slot, &x) in padded[start..].iter_mut().zip(sequence.iter()) {
+            *slot = x;
         }
+        Array1::from_vec(padded).insert_axis(Axis(0)).into_dyn()
+    }
 
-        #[cfg(not(target_arch = "wasm32"))]
-        {
-        let mut r_outputs = Vec::new();
-        let mut out_names = Vec::new();
-        for output_name in output_names {
-            let name = output_name.clone();
-            let output = outputs
-                .get(&name)
-                .ok_or_else(|| ChurOnError::Inference(format!("Output '{}' not found", name)))?;
-
-            // Try numeric f32 output
-            let r_data = match output.try_extract_array::<f32>() {
-                Ok(array_view) => {
-                    let shape: Vec<usize> = array_view.shape().to_vec();
-                    let data: Vec<f32> = array_view.iter().cloned().collect();
-                    let array = ArrayD::from_shape_vec(IxDyn(&shape), data).map_err(|e| {
-                        ChurOnError::DataConversion(format!("Failed to create output array: {}", e))
-                    })?;
-                    let converted = DataConverter::ndarray_f32_to_r(array)?;
-                    converted.into_robj()
-                }
-                Err(_) => match output.try_extract_array::<f64>() {
-                    Ok(array_view) => {
-                        let shape: Vec<usize> = array_view.shape().to_vec();
-                        let data: Vec<f64> = array_view.iter().cloned().collect();
-                        let array = ArrayD::from_shape_vec(IxDyn(&shape), data).map_err(|e| {
-                            ChurOnError::DataConversion(format!(
-                                "Failed to create output array: {}",
-                                e
-                            ))
-                        })?;
-                        let converted = DataConverter::ndarray_f64_to_r(array)?;
-                        converted.into_robj()
-                    }
-                    Err(_) => {
-                        return Err(ChurOnError::DataConversion(format!(
-                            "Unsupported output data type for '{}'",
-                            name
-                        ))
-                        .into());
-                    }
-                },
-            };
-            r_outputs.push(r_data);
-            out_names.push(name);
+    fn padding_options(
+        &self,
+        pad_value: f64,
+        pad_side: &str,
+        max_length: Option<i32>,
+        mask_input: Option<String>,
+    ) -> ChurOnResult<PaddingOptions> {
+        let max_length = match max_length {
+            Some(n) if n < 1 => {
+                return Err(ChurOnError::Validation(
+                    "max_length must be a positive integer".to_string(),
+                ))
+            }
+            n => n.map(|n| n as usize),
+        };
+        if let Some(mask_input) = &mask_input {
+            if !self.input_names.contains(mask_input) {
+                return Err(ChurOnError::Validation(format!(
+                    "Mask input '{}' is not an input of the model",
+                    mask_input
+                )));
+            }
         }
-        let mut result = List::from_values(r_outputs);
-        result.set_names(out_names)?;
-        Ok(result)
+        // Integer inputs are padded with the value as an integer
+        if !(pad_value.is_finite() && pad_value.fract() == 0.0) {
+            for name in &self.input_names {
+                if self.is_integer_input(name)? {
+                    return Err(ChurOnError::Validation(format!(
+                        "pad_value must be a whole number to pad integer input '{}', got {}",
+                        name, pad_value
+                    )));
+                }
+            }
         }
+        Ok(PaddingOptions {
+            pad_value,
+            side: PadSide::parse(pad_side)?,
+            max_length,
+            mask_input,
+        })
     }
-}
 
-impl RSession {
-    fn from_path_with_providers_internal(
-        path: &str,
-        providers: Option<Vec<String>>,
-    ) -> extendr_api::Result<Self> {
-        #[cfg(target_arch = "wasm32")]
-        {
-            Err(extendr_api::Error::EvalError("ONNX Runtime is not supported on WASM".into()))
+    /// Every numeric input of a batch item must hold exactly one example, and
+    /// sequence and map inputs cannot be stacked
+    fn check_single_example(data: &PreparedInputs) -> ChurOnResult<()> {
+        for (input_name, input) in data {
+            match input {
+                InputData::Numeric(_) | InputData::Integer(_)
+                    if input.shape().and_then(|shape| shape.first()) != Some(&1) =>
+                {
+                    return Err(ChurOnError::Validation(format!(
+                        "Batch item input '{}' must hold a single example, got shape {:?}",
+                        input_name,
+                        input.shape().unwrap_or_default()
+                    )));
+                }
+                InputData::Sequence(_) | InputData::Map(_) => {
+                    return Err(ChurOnError::Validation(format!(
+                        "Sequence and map input '{}' cannot be stacked into a batch",
+                        input_name
+                    )));
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    /// Record the outputs of one stacked batch, or its error for every member
+    fn store_batch_outcome(
+        results: &mut [Robj],
+        indices: Vec<usize>,
+        outcome: ChurOnResult<Vec<Robj>>,
+    ) {
+        match outcome {
+            Ok(item_outputs) => {
+                for (index, item_output) in indices.into_iter().zip(item_outputs) {
+                    results[index] = item_output;
+                }
+            }
+            Err(e) => {
+                let error = DataConverter::batch_error(&e.to_string());
+                for index in indices {
+                    results[index] = error.clone();
+                }
+            }
         }
+    }
 
-        #[cfg(not(target_arch = "wasm32"))]
-        {
-        // Check if ONNX Runtime library is available before attempting to initialize
-        // This prevents panics in the ort crate when the library is missing
-        let dylib_path = std::env::var("ORT_DYLIB_PATH")
-            .ok()
-            .filter(|p| !p.is_empty());
+    fn batch_result_list(inputs: &List, results: Vec<Robj>) -> extendr_api::Result<List> {
+        let mut result = List::from_values(results);
+        if let Some(names) = inputs.names() {
+            result.set_names(names.collect::<Vec<_>>())?;
+        }
+        Ok(result)
+    }
 
-        // If ORT_DYLIB_PATH is not set, try to find the library in common locations
-        let dylib_path = if let Some(path) = dylib_path {
-            path
-        } else {
-            // Try to find the library in the package installation directory
-            // This is the default location used by install_onnx_runtime()
-            let pkg_path = std::env::var("R_PACKAGE_DIR").ok()
-                .or_else(|| std::env::var("R_LIBS_USER").ok())
-                .unwrap_or_default();
-
-            let lib_path = if !pkg_path.is_empty() {
-                format!("{}/churon/onnxruntime/lib/onnxruntime.dll", pkg_path)
-            } else {
-                // Fallback: try to load from system path
-                "onnxruntime.dll".to_string()
-            };
+    /// Whether every input declares a dynamic leading dimension, so several
+    /// examples can share one run. Models with a fixed batch size run one at a time.
+    fn has_dynamic_batch_axis(&self) -> bool {
+        self.input_shapes
+            .iter()
+            .all(|shape| !matches!(shape.first(), Some(&dim) if dim >= 0))
+    }
 
-            if std::path::Path::new(&lib_path).exists() {
-                lib_path
-            } else {
-                // Library not found - return error instead of panicking
-                return Err(extendr_api::Error::EvalError(
-                    "ONNX Runtime library not found. Please run install_onnx_runtime() to download it.".into(),
-                ));
+    /// Stack prepared examples along the batch axis. Examples are grouped by input
+    /// shape first, so a chunk with mixed shapes becomes one stacked batch per shape.
+    /// Without `stack` every example forms its own batch.
+    fn stack_batch(
+        prepared: Vec<(usize, PreparedInputs)>,
+        stack: bool,
+    ) -> Vec<(Vec<usize>, ChurOnResult<PreparedInputs>)> {
+        let mut groups: Vec<(Vec<usize>, Vec<PreparedInputs>)> = Vec::new();
+        for (index, data) in prepared {
+            match groups
+                .iter_mut()
+                .find(|(_, members)| stack && Self::same_batch_shape(&members[0], &data))
+            {
+                Some((indices, members)) => {
+                    indices.push(index);
+                    members.push(data);
+                }
+                None => groups.push((vec![index], vec![data])),
             }
-        };
+        }
 
-        // Use Once to ensure ort initialization happens only once
-        // This prevents mutex poisoning when called concurrently
-        ORT_INIT.call_once(|| {
-            // Initialize ONNX Runtime with panic recovery
-            // ort::init_from() may panic if library is invalid, so we catch it
-            let init_result = std::panic::catch_unwind(|| {
-                ort::init_from(&dylib_path).commit()
-            });
+        groups
+            .into_iter()
+            .map(|(indices, members)| (indices, Self::stack_members(members)))
+            .collect()
+    }
 
-            // Log any initialization errors but don't panic
-            // Let R handle the "not installed" case gracefully
-            match init_result {
-                Ok(Ok(_env)) => {
-                    // Environment initialized successfully
-                }
-                Ok(Err(e)) => {
-                    extendr_api::rprintln!("Warning: ONNX Runtime initialization failed: {}", e);
+    /// Concatenate every input of examples that share a shape
+    fn stack_members(members: Vec<PreparedInputs>) -> ChurOnResult<PreparedInputs> {
+        let mut stacked = HashMap::new();
+        for (input_name, input) in &members[0] {
+            let combined = match input {
+                InputData::Numeric(_) => {
+                    let views: Vec<ArrayViewD<f32>> = members
+                        .iter()
+                        .filter_map(|member| match member.get(input_name) {
+                            Some(InputData::Numeric(tensor)) => Some(tensor.view()),
+                            _ => None,
+                        })
+                        .collect();
+                    let array = concatenate(Axis(0), &views).map_err(|e| {
+                        ChurOnError::DataConversion(format!(
+                            "Failed to stack batch input '{}': {}",
+                            input_name, e
+                        ))
+                    })?;
+                    InputData::Numeric(array)
                 }
-                Err(_) => {
-                    extendr_api::rprintln!("Warning: ONNX Runtime initialization panicked (possible corrupted library)");
+                InputData::Integer(_) => {
+                    let views: Vec<ArrayViewD<i64>> = members
+                        .iter()
+                        .filter_map(|member| match member.get(input_name) {
+                            Some(InputData::Integer(tensor)) => Some(tensor.view()),
+                            _ => None,
+                        })
+                        .collect();
+                    let array = concatenate(Axis(0), &views).map_err(|e| {
+                        ChurOnError::DataConversion(format!(
+                            "Failed to stack batch input '{}': {}",
+                            input_name, e
+                        ))
+                    })?;
+                    InputData::Integer(array)
                 }
-            }
-        });
+                InputData::Strings(_) => InputData::Strings(
+                    members
+                        .iter()
+                        .filter_map(|member| match member.get(input_name) {
+                            Some(InputData::Strings(strings)) => Some(strings.clone()),
+                            _ => None,
+                        })
+                        .flatten()
+                        .collect(),
+                ),
+                other => other.clone(),
+            };
+            stacked.insert(input_name.clone(), combined);
+        }
+        Ok(stacked)
+    }
+
+    fn same_batch_shape(a: &PreparedInputs, b: &PreparedInputs) -> bool {
+        a.len() == b.len()
+            && a.iter().all(|(input_name, x)| match (x, b.get(input_name)) {
+                (InputData::Numeric(x), Some(InputData::Numeric(y))) => x.shape() == y.shape(),
+                (InputData::Integer(x), Some(InputData::Integer(y))) => x.shape() == y.shape(),
+                (InputData::Strings(x), Some(InputData::Strings(y))) => x.len() == y.len(),
+                _ => false,
+            })
+    }
+
+    /// Run one stacked batch and split every output back into per-example results,
+    /// trimming padded sequence steps when a layout is given
+    #[cfg(not(target_arch = "wasm32"))]
+    fn run_stacked(
+        &mut self,
+        stacked: PreparedInputs,
+        batch_len: usize,
+        layout: Option<&SequenceLayout>,
+    ) -> ChurOnResult<Vec<Robj>> {
+        let ort_inputs = self.convert_to_ort_values(&stacked)?;
+        let output_names = self.output_names.clone();
+        let mut session = self.lock_session()?;
+        let outputs = session
+            .run(ort_inputs)
+            .map_err(|e| ChurOnError::Inference(format!("Inference execution failed: {}", e)))?;
+
+        let mut per_item: Vec<Vec<Robj>> = vec![Vec::with_capacity(output_names.len()); batch_len];
+        for output_name in &output_names {
+            let output = outputs.get(output_name).ok_or_else(|| {
+                ChurOnError::Inference(format!("Output '{}' not found", output_name))
+            })?;
+            let pieces = DataConverter::split_value_to_r(output_name, output, batch_len, layout)?;
+            for (item, piece) in per_item.iter_mut().zip(pieces) {
+                item.push(piece);
+            }
+        }
+
+        per_item
+            .into_iter()
+            .map(|values| {
+                List::from_names_and_values(&output_names, values)
+                    .map(|list| list.into_robj())
+                    .map_err(|e| ChurOnError::DataConversion(format!("Failed to build batch result: {}", e)))
+            })
+            .collect()
+    }
+
+    /// Run prepared inputs and take ownership of the outputs, in `output_names` order,
+    /// so they can leave the thread that ran them
+    #[cfg(not(target_arch = "wasm32"))]
+    fn run_owned(&mut self, input_data: &PreparedInputs) -> ChurOnResult<Vec<DynValue>> {
+        let ort_inputs = self.convert_to_ort_values(input_data)?;
+        let mut session = self.lock_session()?;
+        Self::take_outputs(&mut session, ort_inputs, &self.output_names)
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn take_outputs<'i, 'v: 'i>(
+        session: &mut Session,
+        ort_inputs: impl Into<SessionInputs<'i, 'v>>,
+        output_names: &[String],
+    ) -> ChurOnResult<Vec<DynValue>> {
+        let ort_inputs: SessionInputs<'i, 'v> = ort_inputs.into();
+        let mut outputs = session
+            .run(ort_inputs)
+            .map_err(|e| ChurOnError::Inference(format!("Inference execution failed: {}", e)))?;
+        output_names
+            .iter()
+            .map(|output_name| {
+                outputs.remove(output_name).ok_or_else(|| {
+                    ChurOnError::Inference(format!("Output '{}' not found", output_name))
+                })
+            })
+            .collect()
+    }
+
+    /// Another handle on the same ORT session, with its own info caches
+    fn share(&self) -> RSession {
+        RSession {
+            session: self.session.clone(),
+            input_names: self.input_names.clone(),
+            output_names: self.output_names.clone(),
+            input_shapes: self.input_shapes.clone(),
+            output_shapes: self.output_shapes.clone(),
+            providers: self.providers.clone(),
+            model_path: self.model_path.clone(),
+            input_info_cache: None,
+            output_info_cache: None,
+            logs: Arc::clone(&self.logs),
+            profiling: self.profiling,
+            options: self.options.clone(),
+            extensions: self.extensions,
+            watch: None,
+        }
+    }
+
+    fn shared_session(&self) -> ChurOnResult<&Arc<Mutex<Session>>> {
+        self.session.as_ref().ok_or(ChurOnError::Closed)
+    }
+
+    fn lock_session(&self) -> ChurOnResult<MutexGuard<'_, Session>> {
+        Self::lock_shared(self.shared_session()?)
+    }
+
+    fn lock_shared(session: &Mutex<Session>) -> ChurOnResult<MutexGuard<'_, Session>> {
+        let poisoned = || ChurOnError::Inference("Session is unusable after a failed inference run".to_string());
+        // An R kernel runs while its session is locked, so waiting here could
+        // mean waiting on itself
+        if ROperator::in_kernel() {
+            return session.try_lock().map_err(|e| match e {
+                TryLockError::WouldBlock => ChurOnError::Inference(
+                    "Session is busy; an R custom operator cannot run the session that called it".to_string(),
+                ),
+                TryLockError::Poisoned(_) => poisoned(),
+            });
+        }
+        session.lock().map_err(|_| poisoned())
+    }
+
+    /// Input names paired with their R objects, which must outlive any values
+    /// borrowed from them by `borrow_input_values`
+    fn named_inputs(inputs: &List) -> Vec<(String, Robj)> {
+        inputs.iter().map(|(name, robj)| (name.to_string(), robj)).collect()
+    }
+
+    /// Build session inputs straight from R objects. Double vectors given to float64
+    /// inputs and integer vectors given to int32 inputs are passed to ORT as views of
+    /// R's own memory; other numeric inputs are converted in a single pass. Strings,
+    /// sequences, maps and unusual element types go through `prepare_input_tensors`.
+    /// Returns whether `auto_batch` added a batch axis.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn borrow_input_values<'a>(
+        &self,
+        input_objs: &'a [(String, Robj)],
+        auto_batch: bool,
+    ) -> ChurOnResult<(Vec<(String, SessionInputValue<'a>)>, bool)> {
+        let mut values = Vec::with_capacity(input_objs.len());
+        let batched = auto_batch && self.batch_axis_missing(input_objs)?;
+        let mut remaining = Vec::new();
+        {
+            let session = self.lock_session()?;
+            for (input_name, robj) in input_objs {
+                let input = session
+                    .inputs
+                    .iter()
+                    .find(|input| &input.name == input_name)
+                    .ok_or_else(|| ChurOnError::Validation(format!("Unknown input name: {}", input_name)))?;
+                let Some(numeric) = RNumeric::from_robj(robj) else {
+                    remaining.push((input_name.as_str(), robj));
+                    continue;
+                };
+                let ValueType::Tensor { ty, shape: declared, .. } = &input.input_type else {
+                    remaining.push((input_name.as_str(), robj));
+                    continue;
+                };
+                let expected: Vec<usize> = declared.iter().map(|&x| if x == -1 { 1 } else { x as usize }).collect();
+                let mut shape = DataConverter::effective_shape(DataConverter::r_dims(robj), &expected);
+                DataConverter::check_element_count(numeric.len(), &shape)?;
+                if batched && matches!(declared.first(), Some(-1) | Some(1)) && declared.len() == shape.len() + 1 {
+                    shape.insert(0, 1);
+                }
+                match numeric.to_session_value(input_name, *ty, shape)? {
+                    Some(value) => values.push((input_name.clone(), value)),
+                    None => remaining.push((input_name.as_str(), robj)),
+                }
+            }
+        }
+
+        if !remaining.is_empty() {
+            let names: Vec<&str> = remaining.iter().map(|(name, _)| *name).collect();
+            let objs: Vec<Robj> = remaining.iter().map(|(_, robj)| (*robj).clone()).collect();
+            let list = List::from_names_and_values(names, objs)
+                .map_err(|e| ChurOnError::DataConversion(format!("Failed to collect inputs: {}", e)))?;
+            let mut input_data = self.prepare_input_tensors(list)?;
+            if batched {
+                self.add_batch_axis(&mut input_data);
+            }
+            values.extend(
+                self.convert_to_ort_values(&input_data)?
+                    .into_iter()
+                    .map(|(name, value)| (name, value.into())),
+            );
+        }
+        Ok((values, batched))
+    }
+
+    /// Add a leading batch axis to numeric inputs that are exactly one rank short of
+    /// a declared shape with a dynamic (or unit) first dimension
+    fn add_batch_axis(&self, input_data: &mut PreparedInputs) {
+        for (input_name, data) in input_data.iter_mut() {
+            let Some(ndim) = data.shape().map(|shape| shape.len()) else {
+                continue;
+            };
+            let declared_shape = self
+                .input_names
+                .iter()
+                .position(|x| x == input_name)
+                .and_then(|idx| self.input_shapes.get(idx));
+            if let Some(declared_shape) = declared_shape {
+                let batch_dim_free = matches!(declared_shape.first(), Some(-1) | Some(1));
+                if batch_dim_free && declared_shape.len() == ndim + 1 {
+                    data.insert_batch_axis();
+                }
+            }
+        }
+    }
+
+    /// Whether `auto_batch` adds a batch axis to these inputs. Arrays one rank short
+    /// of a declared shape with a dynamic (or unit) first dimension lack the axis and
+    /// arrays of the declared rank have it; either every such input lacks it or none
+    /// may.
+    fn batch_axis_missing(&self, inputs: &[(String, Robj)]) -> ChurOnResult<bool> {
+        let mut missing = None;
+        let mut present = None;
+        for (input_name, robj) in inputs {
+            if robj.is_list() {
+                continue;
+            }
+            let declared_shape = self
+                .input_names
+                .iter()
+                .position(|x| x == input_name)
+                .and_then(|idx| self.input_shapes.get(idx));
+            let Some(declared_shape) = declared_shape else {
+                continue;
+            };
+            if !matches!(declared_shape.first(), Some(-1) | Some(1)) {
+                continue;
+            }
+            let ndim = DataConverter::r_dims(robj).len();
+            if declared_shape.len() == ndim + 1 {
+                missing.get_or_insert(input_name);
+            } else if declared_shape.len() == ndim {
+                present.get_or_insert(input_name);
+            }
+        }
+        match (missing, present) {
+            (Some(missing), Some(present)) => Err(ChurOnError::Validation(format!(
+                "auto_batch needs every input with its batch axis or every input without it, \
+                 but '{}' lacks it and '{}' has it",
+                missing, present
+            ))),
+            (missing, _) => Ok(missing.is_some()),
+        }
+    }
+
+    /// Whether an input is declared with an integer element type, such as token ids
+    #[cfg(not(target_arch = "wasm32"))]
+    fn is_integer_input(&self, input_name: &str) -> ChurOnResult<bool> {
+        let session = self.lock_session()?;
+        Ok(session.inputs.iter().any(|input| {
+            input.name == input_name
+                && matches!(
+                    input.input_type,
+                    ValueType::Tensor { ty: TensorElementType::Int64 | TensorElementType::Int32, .. }
+                )
+        }))
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    fn is_integer_input(&self, _input_name: &str) -> ChurOnResult<bool> {
+        Ok(false)
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn prepare_structured_input(
+        &self,
+        input_name: &str,
+        input_robj: &Robj,
+    ) -> ChurOnResult<Option<InputData>> {
+        let session = self.lock_session()?;
+        let declared_type = session
+            .inputs
+            .iter()
+            .find(|input| input.name == input_name)
+            .map(|input| &input.input_type);
+
+        match declared_type {
+            Some(value_type @ (ValueType::Sequence(_) | ValueType::Map { .. })) => {
+                DataConverter::r_obj_to_input_data(input_name, input_robj, value_type).map(Some)
+            }
+            _ => Ok(None),
+        }
+    }
+
+    fn convert_to_ort_values(
+        &self,
+        input_data: &PreparedInputs,
+    ) -> ChurOnResult<HashMap<String, Value>> {
+        #[cfg(target_arch = "wasm32")]
+        {
+            // Return empty hashmap for WASM - ort crate not available on wasm
+            Ok(HashMap::new())
+        }
+
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+        let mut values: HashMap<String, Value> = HashMap::new();
+
+        // Build each value against the type the model declares for that input
+        let session = self.lock_session()?;
+        for input in &session.inputs {
+            if let Some(data) = input_data.get(&input.name) {
+                let value = DataConverter::input_data_to_value(&input.name, data, &input.input_type)?;
+                values.insert(input.name.clone(), value);
+            }
+        }
+
+        Ok(values)
+        }
+    }
+
+    fn extract_outputs(
+        outputs: SessionOutputs,
+        output_names: &[String],
+        drop_batch: bool,
+    ) -> extendr_api::Result<List> {
+        #[cfg(target_arch = "wasm32")]
+        {
+            // On wasm, ort is not available, so return empty list
+            Ok(List::from_values(Vec::<Robj>::new()))
+        }
+
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+        let mut r_outputs = Vec::new();
+        let mut out_names = Vec::new();
+        for output_name in output_names {
+            let name = output_name.clone();
+            let output = outputs
+                .get(&name)
+                .ok_or_else(|| ChurOnError::Inference(format!("Output '{}' not found", name)))?;
+
+            let mut r_data = DataConverter::value_to_r(&name, output)?;
+            if drop_batch {
+                DataConverter::drop_batch_axis(&name, &mut r_data, output)?;
+            }
+            r_outputs.push(r_data);
+            out_names.push(name);
+        }
+        let mut result = List::from_values(r_outputs);
+        result.set_names(out_names)?;
+        Ok(result)
+        }
+    }
+}
+
+impl RSession {
+    fn from_path_with_options(path: &str, options: &SessionOptions) -> extendr_api::Result<Self> {
+        Self::from_path_with_logs(path, options, Arc::new(LogBuffer::new(options.log_level)))
+    }
+
+    /// Build a session whose ONNX Runtime logger writes into `logs`
+    fn from_path_with_logs(
+        path: &str,
+        options: &SessionOptions,
+        logs: Arc<LogBuffer>,
+    ) -> extendr_api::Result<Self> {
+        #[cfg(target_arch = "wasm32")]
+        {
+            Err(extendr_api::Error::EvalError("ONNX Runtime is not supported on WASM".into()))
+        }
+
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+        // A failed initialization is reported on every session creation until
+        // reinitialize_runtime() succeeds
+        OrtRuntime::ensure_initialized()?;
 
-        // Check if ONNX Runtime was initialized successfully
-        // If initialization failed, we can't proceed
-        let execution_providers = Self::get_execution_providers(providers)?;
-        let session = Session::builder()
+        let execution_providers = Self::get_execution_providers(options.providers.clone())?;
+        let mut builder = Session::builder()
             .map_err(|e| {
                 ChurOnError::ModelLoad(format!("Failed to create session builder: {}", e))
             })?
@@ -530,14 +2083,61 @@ impl RSession {
             })?
             .with_intra_threads(1)
             .map_err(|e| ChurOnError::ModelLoad(format!("Failed to set intra threads: {}", e)))?
+            .with_log_id(format!("churon-session-{}", SESSION_COUNTER.fetch_add(1, Ordering::Relaxed)))
+            .and_then(|builder| builder.with_log_level(options.log_level.to_ort()))
+            .and_then(|builder| builder.with_logger(LogBuffer::logger(Arc::clone(&logs))))
+            .map_err(|e| ChurOnError::ModelLoad(format!("Failed to configure logging: {}", e)))?
             .with_execution_providers(execution_providers)
             .map_err(|e| {
                 ChurOnError::Provider(format!("Failed to set execution providers: {}", e))
-            })?
+            })?;
+        if let Some(prefix) = &options.profile_prefix {
+            builder = builder
+                .with_profiling(prefix)
+                .map_err(|e| ChurOnError::ModelLoad(format!("Failed to enable profiling: {}", e)))?;
+        }
+        for library in &options.custom_op_libraries {
+            builder = builder.with_operator_library(library).map_err(|e| {
+                ChurOnError::ModelLoad(format!("Failed to register custom operator library {}: {}", library, e))
+            })?;
+        }
+        builder = CustomOps::builtin_domain()
+            .and_then(|domain| builder.with_operators(domain))
+            .map_err(|e| ChurOnError::ModelLoad(format!("Failed to register churon's built-in operators: {}", e)))?;
+        let r_domains = CustomOps::r_domains(&options.r_operators)
+            .map_err(|e| ChurOnError::ModelLoad(format!("Failed to register R custom operators: {}", e)))?;
+        for domain in r_domains {
+            builder = builder
+                .with_operators(domain)
+                .map_err(|e| ChurOnError::ModelLoad(format!("Failed to register R custom operators: {}", e)))?;
+        }
+        for (dimension, size) in &options.dimension_overrides {
+            builder = if Self::is_denotation(dimension) {
+                builder.with_dimension_override_by_denotation(dimension, *size)
+            } else {
+                builder.with_dimension_override(dimension, *size)
+            }
+            .map_err(|e| ChurOnError::ModelLoad(format!("Failed to override dimension '{}': {}", dimension, e)))?;
+        }
+        let extensions = options.extensions && Self::extensions_available();
+        if extensions {
+            builder = builder.with_extensions().map_err(|e| {
+                ChurOnError::ModelLoad(format!("Failed to enable onnxruntime-extensions: {}", e))
+            })?;
+        } else if options.extensions {
+            logs.push(
+                LogSeverity::Warning,
+                "churon",
+                "",
+                "onnxruntime-extensions is not built into the loaded ONNX Runtime; its operators are not registered",
+            );
+        }
+        let session = builder
             .commit_from_file(Path::new(path))
             .map_err(|e| {
                 ChurOnError::ModelLoad(format!("Failed to load model from {}: {}", path, e))
             })?;
+        OrtRuntime::mark_session_created();
         let inputs: Vec<_> = session.inputs.iter().collect();
         let outputs: Vec<_> = session.outputs.iter().collect();
         let input_names: Vec<String> = inputs
@@ -550,14 +2150,14 @@ impl RSession {
             .collect();
         let input_shapes: Vec<Vec<i64>> = inputs
             .iter()
-            .map(|_| vec![-1]) // Placeholder - dynamic dimension
+            .map(|input| Self::pinned_shape(&input.input_type, &options.dimension_overrides))
             .collect();
         let output_shapes: Vec<Vec<i64>> = outputs
             .iter()
-            .map(|_| vec![-1]) // Placeholder - dynamic dimension
+            .map(|output| Self::pinned_shape(&output.output_type, &options.dimension_overrides))
             .collect();
         Ok(RSession {
-            session,
+            session: Some(Arc::new(Mutex::new(session))),
             input_names,
             output_names,
             input_shapes,
@@ -566,10 +2166,81 @@ impl RSession {
             model_path: path.to_string(),
             input_info_cache: None,
             output_info_cache: None,
+            logs,
+            profiling: options.profile_prefix.is_some(),
+            options: options.clone(),
+            extensions,
+            watch: None,
         })
         }
     }
 
+    /// Whether the loaded ONNX Runtime was built with onnxruntime-extensions. Checked
+    /// on a throwaway builder, since a failed `with_extensions` consumes the builder.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn extensions_available() -> bool {
+        Session::builder().and_then(|builder| builder.with_extensions()).is_ok()
+    }
+
+    /// Declared tensor shape with the dimensions named in `overrides` pinned.
+    /// ORT applies the overrides to the graph itself; this keeps the reported
+    /// shapes right whether or not it reports the pinned sizes back. ORT's type
+    /// info carries no dimension denotations, so denotation overrides such as
+    /// `DATA_BATCH` leave their dimensions dynamic here.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn pinned_shape(value_type: &ValueType, overrides: &[(String, i64)]) -> Vec<i64> {
+        match value_type {
+            ValueType::Tensor { shape, dimension_symbols, .. } => {
+                Self::pin_dimensions(shape, dimension_symbols, overrides)
+            }
+            _ => Self::declared_shape(value_type),
+        }
+    }
+
+    /// Replace each dynamic dimension whose symbol has an override with its size
+    fn pin_dimensions(shape: &[i64], symbols: &[String], overrides: &[(String, i64)]) -> Vec<i64> {
+        shape
+            .iter()
+            .enumerate()
+            .map(|(i, &dim)| {
+                let pinned = symbols
+                    .get(i)
+                    .and_then(|symbol| overrides.iter().find(|(name, _)| name == symbol))
+                    .map(|&(_, size)| size);
+                match pinned {
+                    Some(size) if dim < 0 => size,
+                    _ => dim,
+                }
+            })
+            .collect()
+    }
+
+    /// Whether an override names a standard ONNX dimension denotation rather
+    /// than a symbolic dimension
+    fn is_denotation(dimension: &str) -> bool {
+        const DENOTATIONS: [&str; 8] = [
+            "DATA_BATCH",
+            "DATA_CHANNEL",
+            "DATA_TIME",
+            "DATA_FEATURE",
+            "FILTER_IN_CHANNEL",
+            "FILTER_OUT_CHANNEL",
+            "FILTER_SPATIAL",
+            "DATA_SPATIAL",
+        ];
+        DENOTATIONS.contains(&dimension)
+    }
+
+    /// Declared tensor shape, with -1 for dynamic dimensions. Non-tensor values
+    /// (sequences, maps) have no fixed shape.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn declared_shape(value_type: &ValueType) -> Vec<i64> {
+        value_type
+            .tensor_shape()
+            .map(|shape| shape.to_vec())
+            .unwrap_or_else(|| vec![-1])
+    }
+
     fn get_execution_providers(
         providers: Option<Vec<String>>,
     ) -> ChurOnResult<Vec<ExecutionProviderDispatch>> {
@@ -644,79 +2315,2745 @@ impl RSession {
     }
 }
 
-pub struct DataConverter;
-
-impl DataConverter {
-    /// Convert R object (vector, matrix, array) to ndarray f32
-    pub fn r_obj_to_ndarray_f32(
-        robj: &Robj,
-        expected_shape: &[usize],
-    ) -> ChurOnResult<ArrayD<f32>> {
-        // Get dimensions from R object
-        let actual_shape: Vec<usize> = if let Some(dims) = robj.dim() {
-            dims.iter().map(|d| d.inner() as usize).collect()
-        } else {
-            vec![robj.len()]
-        };
+/// A pool of independent sessions over the same model, so several inferences can run
+/// at once on Rust threads
+#[extendr]
+pub struct RSessionPool {
+    sessions: Vec<RSession>,
+    model_path: String,
+}
 
-        // Determine effective shape
-        let effective_shape: Vec<usize> = if expected_shape.iter().any(|&x| x == 0 || x == 1) {
-            // If expected shape has zeros or ones, use actual shape
-            actual_shape.clone()
-        } else {
-            expected_shape.to_vec()
+#[extendr]
+impl RSessionPool {
+    /// Every session is built from the same options as `RSession::from_path`
+    pub fn from_path(
+        path: &str,
+        #[default = "NULL"] size: Nullable<i32>,
+        #[default = "NULL"] options: Nullable<List>,
+    ) -> extendr_api::Result<Self> {
+        let size = match size.into_option() {
+            Some(n) if n < 1 => {
+                return Err(ChurOnError::Validation("Pool size must be a positive integer".to_string()).into())
+            }
+            Some(n) => n as usize,
+            None => std::thread::available_parallelism().map(|n| n.get()).unwrap_or(1),
         };
-
-        // Extract numeric data as f64 first, then convert to f32
-        let data_f32: Vec<f32> = if let Some(doubles) = robj.as_real_slice() {
-            doubles.iter().map(|&x| x as f32).collect()
-        } else if let Some(ints) = robj.as_integer_slice() {
-            ints.iter().map(|&x| x as f32).collect()
-        } else {
-            return Err(ChurOnError::DataConversion(
-                "Input must be numeric (integer or real)".to_string(),
-            ));
+        let options = match options.into_option() {
+            Some(options) => SessionOptions::from_list(&options)?,
+            None => SessionOptions::default(),
         };
-
-        let total_elements: usize = effective_shape.iter().product();
-        if data_f32.len() != total_elements {
-            return Err(ChurOnError::DataConversion(format!(
-                "Data length {} doesn't match expected shape {:?} (expected {} elements)",
-                data_f32.len(),
-                effective_shape,
-                total_elements
-            )));
+        // Pool sessions run on worker threads, where R functions cannot be called
+        if !options.r_operators.is_empty() {
+            return Err(ChurOnError::Validation(
+                "R custom operators cannot be used in a session pool".to_string(),
+            )
+            .into());
         }
+        let sessions = (0..size)
+            .map(|_| RSession::from_path_with_options(path, &options))
+            .collect::<extendr_api::Result<Vec<_>>>()?;
+        Ok(RSessionPool {
+            sessions,
+            model_path: path.to_string(),
+        })
+    }
 
-        ArrayD::from_shape_vec(IxDyn(&effective_shape), data_f32)
-            .map_err(|e| ChurOnError::DataConversion(format!("Failed to create ndarray: {}", e)))
+    pub fn get_size(&self) -> i32 {
+        self.sessions.len() as i32
     }
 
-    pub fn ndarray_f32_to_r(array: ArrayD<f32>) -> ChurOnResult<Doubles> {
-        let data: Vec<f64> = array.iter().map(|&x| x as f64).collect();
-        Ok(Doubles::from_values(data))
+    pub fn get_model_path(&self) -> String {
+        self.model_path.clone()
     }
 
-    pub fn ndarray_f64_to_r(array: ArrayD<f64>) -> ChurOnResult<Doubles> {
-        let data: Vec<f64> = array.iter().cloned().collect();
-        Ok(Doubles::from_values(data))
+    fn run_parallel(&mut self, inputs: List, #[default = "FALSE"] auto_batch: bool) -> extendr_api::Result<List> {
+        #[cfg(target_arch = "wasm32")]
+        {
+            // Return empty list for WASM - ort crate not available on wasm
+            Ok(List::from_values(Vec::<Robj>::new()))
+        }
+
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+        let template = &self.sessions[0];
+        template.validate_session()?;
+
+        // R objects can only be touched on this thread, so every item is converted to
+        // plain Rust data before the workers start
+        let items: Vec<Robj> = inputs.values().collect();
+        let mut results: Vec<Robj> = vec![Robj::from(()); items.len()];
+        let mut jobs = Vec::new();
+        for (index, item) in items.iter().enumerate() {
+            match template.prepare_pool_item(item, auto_batch) {
+                Ok((data, batched)) => jobs.push((index, data, batched)),
+                Err(e) => results[index] = DataConverter::batch_error(&e.to_string()),
+            }
+        }
+
+        let outcomes = Self::run_jobs(&mut self.sessions, &jobs);
+
+        let output_names = &self.sessions[0].output_names;
+        for ((index, _, batched), outcome) in jobs.iter().zip(outcomes) {
+            let converted = outcome.and_then(|values| {
+                DataConverter::values_to_r_list(output_names, &values, *batched)
+            });
+            results[*index] = match converted {
+                Ok(output) => output,
+                Err(e) => DataConverter::batch_error(&e.to_string()),
+            };
+        }
+
+        RSession::batch_result_list(&inputs, results)
+        }
     }
 }
 
-extendr_module! {
-    mod churon;
-    impl RSession;
-    impl TensorInfo;
+impl RSessionPool {
+    /// Run every job on the pool's sessions, one worker thread per session. Workers
+    /// take the next unclaimed job until none are left; outcomes keep job order.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn run_jobs(
+        sessions: &mut [RSession],
+        jobs: &[(usize, PreparedInputs, bool)],
+    ) -> Vec<ChurOnResult<Vec<DynValue>>> {
+        let next_job = AtomicUsize::new(0);
+        let mut outcomes: Vec<Option<ChurOnResult<Vec<DynValue>>>> = (0..jobs.len()).map(|_| None).collect();
+
+        std::thread::scope(|scope| {
+            let workers: Vec<_> = sessions
+                .iter_mut()
+                .take(jobs.len())
+                .map(|session| {
+                    let next_job = &next_job;
+                    scope.spawn(move || {
+                        let mut finished = Vec::new();
+                        loop {
+                            let job = next_job.fetch_add(1, Ordering::Relaxed);
+                            let Some((_, data, _)) = jobs.get(job) else {
+                                break;
+                            };
+                            finished.push((job, session.run_owned(data)));
+                        }
+                        finished
+                    })
+                })
+                .collect();
+
+            for worker in workers {
+                // A panicking worker leaves its jobs unfinished; they are reported below
+                if let Ok(finished) = worker.join() {
+                    for (job, outcome) in finished {
+                        outcomes[job] = Some(outcome);
+                    }
+                }
+            }
+        });
+
+        outcomes
+            .into_iter()
+            .map(|outcome| {
+                outcome.unwrap_or_else(|| {
+                    Err(ChurOnError::Inference("Inference worker thread panicked".to_string()))
+                })
+            })
+            .collect()
+    }
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+/// Initializes the process-wide ONNX Runtime environment and remembers the outcome
+pub struct OrtRuntime;
 
-    #[test]
-    fn test_tensor_info_creation() {
-        let tensor_info = TensorInfo::new(
-            "test_tensor".to_string(),
+#[cfg(not(target_arch = "wasm32"))]
+impl OrtRuntime {
+    /// Initialize on first use from the located library, or return the stored outcome
+    /// of an earlier attempt
+    pub fn ensure_initialized() -> ChurOnResult<()> {
+        let mut state = Self::state()?;
+        if let Some(outcome) = &state.outcome {
+            return outcome.clone().map(|_| ());
+        }
+
+        // A missing library is not an initialization attempt, so installing it
+        // afterwards is enough to recover
+        let dylib_path = RuntimeLocator::locate()
+            .map_err(|tried| ChurOnError::RuntimeInit(RuntimeLocator::not_found_message(&tried)))?;
+        let outcome = Self::initialize(&mut state, &dylib_path.to_string_lossy());
+        state.outcome = Some(outcome.clone());
+        outcome.map(|_| ())
+    }
+
+    /// Retry initialization from `path`. Only possible until the first session is
+    /// created, and only with the same library once one has been handed to `ort`.
+    pub fn reinitialize(path: &str) -> ChurOnResult<String> {
+        let mut state = Self::state()?;
+        if state.session_created {
+            return Err(ChurOnError::RuntimeInit(
+                "ONNX Runtime cannot be reinitialized after a session has been created; restart R to use another library".to_string(),
+            ));
+        }
+        if let Some(Ok(current)) = &state.outcome {
+            if current == path {
+                return Ok(current.clone());
+            }
+        }
+        let outcome = Self::initialize(&mut state, path);
+        state.outcome = Some(outcome.clone());
+        outcome
+    }
+
+    /// Outcome of the last initialization attempt, without attempting one
+    pub fn outcome() -> Option<ChurOnResult<String>> {
+        ORT_RUNTIME.lock().ok()?.outcome.clone()
+    }
+
+    /// Library path in use, once initialization has succeeded
+    pub fn library_path() -> Option<String> {
+        let state = ORT_RUNTIME.lock().ok()?;
+        match &state.outcome {
+            Some(Ok(path)) => Some(path.clone()),
+            _ => None,
+        }
+    }
+
+    /// Version string reported by the library ort loaded from `path`, such as "1.23.2"
+    pub fn loaded_version(path: &str) -> ChurOnResult<String> {
+        // SAFETY: the library is already loaded by ort, so this only takes another
+        // reference to it
+        let library = unsafe { libloading::Library::new(path) }.map_err(|e| {
+            ChurOnError::RuntimeInit(format!("Failed to load '{}': {}", path, e))
+        })?;
+        Self::library_version(&library, path)
+    }
+
+    /// Version string reported through the ONNX Runtime API base of `library`
+    fn library_version(library: &libloading::Library, path: &str) -> ChurOnResult<String> {
+        // SAFETY: OrtGetApiBase takes no arguments and returns a pointer to a static
+        // table whose GetVersionString returns a static C string
+        unsafe {
+            let get_api_base = library
+                .get::<unsafe extern "system" fn() -> *const ort::sys::OrtApiBase>(b"OrtGetApiBase")
+                .map_err(|e| ChurOnError::RuntimeInit(format!("OrtGetApiBase not found in '{}': {}", path, e)))?;
+            let base = get_api_base();
+            if base.is_null() {
+                return Err(ChurOnError::RuntimeInit(format!(
+                    "'{}' returned no API base",
+                    path
+                )));
+            }
+            let version = ((*base).GetVersionString)();
+            Ok(CStr::from_ptr(version).to_string_lossy().into_owned())
+        }
+    }
+
+    /// Execution providers the loaded library was built with
+    pub fn available_providers() -> ChurOnResult<Vec<String>> {
+        let api = ort::api();
+        let mut names: *mut *mut c_char = std::ptr::null_mut();
+        let mut count: c_int = 0;
+        // SAFETY: ONNX Runtime fills `names` with `count` C strings, which stay valid
+        // until they are released below
+        unsafe {
+            let status = (api.GetAvailableProviders)(&mut names, &mut count);
+            if !status.0.is_null() {
+                (api.ReleaseStatus)(status.0);
+                return Err(ChurOnError::Provider(
+                    "Failed to list available execution providers".to_string(),
+                ));
+            }
+            let providers = (0..count.max(0) as usize)
+                .map(|i| CStr::from_ptr(*names.add(i)).to_string_lossy().into_owned())
+                .collect();
+            let status = (api.ReleaseAvailableProviders)(names, count);
+            if !status.0.is_null() {
+                (api.ReleaseStatus)(status.0);
+            }
+            Ok(providers)
+        }
+    }
+
+    pub fn mark_session_created() {
+        if let Ok(mut state) = ORT_RUNTIME.lock() {
+            state.session_created = true;
+        }
+    }
+
+    fn initialize(state: &mut RuntimeState, path: &str) -> ChurOnResult<String> {
+        if let Some(committed) = &state.committed_path {
+            if committed != path {
+                return Err(ChurOnError::RuntimeInit(format!(
+                    "ONNX Runtime is already bound to '{}' in this R session; restart R to use '{}'",
+                    committed, path
+                )));
+            }
+        }
+
+        // ort keeps the first library path for the life of the process, so a broken
+        // library is probed here before it is handed over
+        if state.committed_path.is_none() {
+            Self::probe_library(path)?;
+            state.committed_path = Some(path.to_string());
+        }
+
+        // ort::init_from() panics rather than failing when the library is unusable
+        let init_result = std::panic::catch_unwind(|| {
+            ort::init_from(path)
+                .with_logger(Box::new(|level, _category, id, location, message| {
+                    ENVIRONMENT_LOG.push(LogSeverity::from_ort(level), id, location, message)
+                }))
+                .commit()
+        });
+        match init_result {
+            Ok(Ok(_)) => Ok(path.to_string()),
+            Ok(Err(e)) => Err(ChurOnError::RuntimeInit(format!(
+                "Failed to create environment from '{}': {}",
+                path, e
+            ))),
+            Err(panic) => {
+                let reason = panic
+                    .downcast_ref::<String>()
+                    .cloned()
+                    .or_else(|| panic.downcast_ref::<&str>().map(|s| s.to_string()))
+                    .unwrap_or_else(|| "possible corrupted library".to_string());
+                Err(ChurOnError::RuntimeInit(format!(
+                    "Loading '{}' panicked: {}",
+                    path, reason
+                )))
+            }
+        }
+    }
+
+    /// Check that `path` is a loadable ONNX Runtime library of a version `ort` supports.
+    /// This runs before ort has loaded anything, so it loads the candidate itself.
+    fn probe_library(path: &str) -> ChurOnResult<()> {
+        // SAFETY: loading runs the library's initializers, as `ort::init_from` would
+        // for the same path right after this check; this handle is released when
+        // `library` drops
+        let library = unsafe { libloading::Library::new(path) }.map_err(|e| {
+            ChurOnError::RuntimeInit(format!("Failed to load '{}': {}", path, e))
+        })?;
+        let version = Self::library_version(&library, path)?;
+        Self::check_compatible(&version).map_err(|reason| {
+            ChurOnError::RuntimeInit(format!("'{}' cannot be used: {}", path, reason))
+        })
+    }
+
+    /// Check an ONNX Runtime version string against the C API version `ort` requests.
+    /// Older libraries do not provide that API and crash or panic when loaded; newer
+    /// 1.x releases keep it.
+    pub fn check_compatible(version: &str) -> std::result::Result<(), String> {
+        let mut parts = version.trim().split('.').map(|part| part.parse::<u32>().ok());
+        let (major, minor) = match (parts.next().flatten(), parts.next().flatten()) {
+            (Some(major), Some(minor)) => (major, minor),
+            _ => return Err(format!("unrecognized ONNX Runtime version '{}'", version)),
+        };
+        if major == 1 && minor >= ort::MINOR_VERSION {
+            return Ok(());
+        }
+        Err(format!(
+            "ONNX Runtime {} is not supported; ort {} requires ONNX Runtime 1.{}.0 or a later 1.x release \
+             (C API version {}). Run install_onnx_runtime() to install a supported version",
+            version,
+            ORT_CRATE_VERSION,
+            ort::MINOR_VERSION,
+            ort::MINOR_VERSION
+        ))
+    }
+
+    fn state() -> ChurOnResult<MutexGuard<'static, RuntimeState>> {
+        ORT_RUNTIME.lock().map_err(|_| {
+            ChurOnError::RuntimeInit("Initialization state is unavailable".to_string())
+        })
+    }
+}
+
+/// Finds the ONNX Runtime shared library when `ORT_DYLIB_PATH` does not point at one
+pub struct RuntimeLocator;
+
+#[cfg(not(target_arch = "wasm32"))]
+impl RuntimeLocator {
+    /// Locate the library, or return every path that was tried. `ORT_DYLIB_PATH` wins,
+    /// then `CHURON_ORT_HOME`, the churon install in each of `.libPaths()`, and the
+    /// platform's library search paths.
+    pub fn locate() -> std::result::Result<PathBuf, Vec<PathBuf>> {
+        let mut tried = Vec::new();
+        if let Some(path) = Self::env_path("ORT_DYLIB_PATH") {
+            if path.is_file() {
+                return Ok(path);
+            }
+            tried.push(path);
+        }
+
+        let library_name = Self::library_name();
+        for dir in Self::search_dirs() {
+            let candidate = dir.join(library_name);
+            if candidate.is_file() {
+                return Ok(candidate);
+            }
+            tried.push(candidate);
+            if let Some(versioned) = Self::find_versioned(&dir) {
+                return Ok(versioned);
+            }
+        }
+        Err(tried)
+    }
+
+    pub fn not_found_message(tried: &[PathBuf]) -> String {
+        let locations: Vec<String> = tried
+            .iter()
+            .map(|path| format!("  {}", path.display()))
+            .collect();
+        format!(
+            "ONNX Runtime library not found. Please run install_onnx_runtime() to download it, \
+             or set CHURON_ORT_HOME to an ONNX Runtime installation.\nLocations tried:\n{}",
+            locations.join("\n")
+        )
+    }
+
+    /// Platform file name of the ONNX Runtime shared library
+    pub fn library_name() -> &'static str {
+        if cfg!(target_os = "windows") {
+            "onnxruntime.dll"
+        } else if cfg!(target_os = "macos") {
+            "libonnxruntime.dylib"
+        } else {
+            "libonnxruntime.so"
+        }
+    }
+
+    /// Whether `file_name` is a versioned build of the library, such as
+    /// `libonnxruntime.so.1` or `libonnxruntime.1.23.2.dylib`
+    pub fn is_versioned_library(file_name: &str) -> bool {
+        if cfg!(target_os = "windows") {
+            false
+        } else if cfg!(target_os = "macos") {
+            file_name.starts_with("libonnxruntime.")
+                && file_name.ends_with(".dylib")
+                && file_name != "libonnxruntime.dylib"
+        } else {
+            file_name.starts_with("libonnxruntime.so.")
+        }
+    }
+
+    fn find_versioned(dir: &Path) -> Option<PathBuf> {
+        let mut versioned: Vec<PathBuf> = std::fs::read_dir(dir)
+            .ok()?
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.path())
+            .filter(|path| {
+                path.is_file()
+                    && path
+                        .file_name()
+                        .and_then(|name| name.to_str())
+                        .is_some_and(Self::is_versioned_library)
+            })
+            .collect();
+        // Shortest name first, so `libonnxruntime.so.1` is preferred over `libonnxruntime.so.1.23.2`
+        versioned.sort_by_key(|path| path.as_os_str().len());
+        versioned.into_iter().next()
+    }
+
+    /// Directories searched for the library, in priority order and without duplicates
+    fn search_dirs() -> Vec<PathBuf> {
+        let mut dirs = Vec::new();
+        if let Some(home) = Self::env_path("CHURON_ORT_HOME") {
+            dirs.push(home.join("lib"));
+            dirs.push(home);
+        }
+
+        // install_onnx_runtime() places the library inside the installed package
+        if let Some(package_dir) = Self::env_path("R_PACKAGE_DIR") {
+            dirs.push(package_dir.join("onnxruntime").join("lib"));
+        }
+        for lib_path in Self::r_lib_paths() {
+            dirs.push(lib_path.join("churon").join("onnxruntime").join("lib"));
+        }
+
+        let search_var = if cfg!(target_os = "windows") {
+            "PATH"
+        } else if cfg!(target_os = "macos") {
+            "DYLD_LIBRARY_PATH"
+        } else {
+            "LD_LIBRARY_PATH"
+        };
+        if let Some(paths) = std::env::var_os(search_var) {
+            dirs.extend(std::env::split_paths(&paths).filter(|dir| !dir.as_os_str().is_empty()));
+        }
+
+        let system_dirs: &[&str] = if cfg!(target_os = "windows") {
+            &["C:\\Windows\\System32"]
+        } else if cfg!(target_os = "macos") {
+            &["/opt/homebrew/lib", "/usr/local/lib", "/usr/lib"]
+        } else {
+            &[
+                "/usr/local/lib",
+                "/usr/lib",
+                "/usr/lib64",
+                "/usr/lib/x86_64-linux-gnu",
+                "/usr/lib/aarch64-linux-gnu",
+                "/lib",
+            ]
+        };
+        dirs.extend(system_dirs.iter().map(PathBuf::from));
+
+        let mut unique = Vec::with_capacity(dirs.len());
+        for dir in dirs {
+            if !unique.contains(&dir) {
+                unique.push(dir);
+            }
+        }
+        unique
+    }
+
+    fn r_lib_paths() -> Vec<PathBuf> {
+        eval_string(".libPaths()")
+            .ok()
+            .and_then(|paths| paths.as_str_vector().map(|v| v.into_iter().map(PathBuf::from).collect()))
+            .unwrap_or_default()
+    }
+
+    fn env_path(name: &str) -> Option<PathBuf> {
+        std::env::var_os(name)
+            .filter(|value| !value.is_empty())
+            .map(PathBuf::from)
+    }
+}
+
+/// Outputs of a finished asynchronous run
+#[cfg(not(target_arch = "wasm32"))]
+type AsyncOutputs = Vec<DynValue>;
+#[cfg(target_arch = "wasm32")]
+type AsyncOutputs = Vec<Value>;
+
+/// Outcome of an asynchronous run, filled in by the worker thread
+type AsyncSlot = Arc<(Mutex<Option<ChurOnResult<AsyncOutputs>>>, Condvar)>;
+
+/// How long a blocking wait sleeps between checks for a user interrupt
+const INTERRUPT_POLL: Duration = Duration::from_millis(100);
+
+extern "C" {
+    fn R_CheckUserInterrupt();
+    fn R_ToplevelExec(fun: Option<unsafe extern "C" fn(*mut c_void)>, data: *mut c_void) -> c_int;
+}
+
+/// Whether the user has interrupted R (Ctrl-C or Esc) since it last checked. R's
+/// check jumps out of its caller on an interrupt, so it runs inside
+/// `R_ToplevelExec`, which stops the jump and reports it as a failed call.
+fn user_interrupted() -> bool {
+    unsafe extern "C" fn check(_: *mut c_void) {
+        R_CheckUserInterrupt();
+    }
+    // SAFETY: only called from R's main thread, and no Rust frame is unwound
+    // since R_ToplevelExec catches the jump
+    extendr_api::thread_safety::single_threaded(|| unsafe {
+        R_ToplevelExec(Some(check), std::ptr::null_mut()) == 0
+    })
+}
+
+/// Handle to an inference running on a background thread
+#[extendr]
+pub struct RInferenceHandle {
+    slot: AsyncSlot,
+    output_names: Vec<String>,
+    drop_batch: bool,
+}
+
+#[extendr]
+impl RInferenceHandle {
+    pub fn is_done(&self) -> bool {
+        self.slot.0.lock().map(|outcome| outcome.is_some()).unwrap_or(true)
+    }
+
+    /// Block until the run finishes or `timeout` seconds pass. Returns whether the
+    /// run has finished.
+    pub fn wait(&self, #[default = "NULL"] timeout: Nullable<f64>) -> extendr_api::Result<bool> {
+        let timeout = match timeout.into_option() {
+            Some(seconds) if seconds.is_nan() || seconds < 0.0 => {
+                return Err(ChurOnError::Validation("timeout must be a non-negative number".to_string()).into())
+            }
+            // Timeouts too large for a Duration are treated as waiting forever
+            Some(seconds) => Duration::try_from_secs_f64(seconds).ok(),
+            None => None,
+        };
+        let outcome = self.wait_interruptibly(timeout)?;
+        Ok(outcome.is_some())
+    }
+
+    /// Wait for the run to finish and return its outputs, or raise its error
+    pub fn result(&self) -> extendr_api::Result<Robj> {
+        #[cfg(target_arch = "wasm32")]
+        {
+            Ok(List::from_values(Vec::<Robj>::new()).into_robj())
+        }
+
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+        let outcome = self.wait_interruptibly(None)?;
+        match outcome.as_ref() {
+            Some(Ok(values)) => {
+                Ok(DataConverter::values_to_r_list(&self.output_names, values, self.drop_batch)?)
+            }
+            Some(Err(e)) => Err(e.clone().into()),
+            None => Err(Self::poisoned().into()),
+        }
+        }
+    }
+}
+
+impl RInferenceHandle {
+    /// Start running `ort_inputs` on a new thread. The thread waits for any run
+    /// already holding the session.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn spawn(
+        session: Arc<Mutex<Session>>,
+        ort_inputs: HashMap<String, Value>,
+        output_names: Vec<String>,
+        drop_batch: bool,
+    ) -> Self {
+        let slot: AsyncSlot = Arc::new((Mutex::new(None), Condvar::new()));
+        let worker_slot = Arc::clone(&slot);
+        let worker_output_names = output_names.clone();
+
+        std::thread::spawn(move || {
+            let outcome = std::panic::catch_unwind(AssertUnwindSafe(|| {
+                let mut session = RSession::lock_shared(&session)?;
+                RSession::take_outputs(&mut session, ort_inputs, &worker_output_names)
+            }))
+            .unwrap_or_else(|_| Err(ChurOnError::Inference("Inference worker thread panicked".to_string())));
+
+            let (lock, done) = &*worker_slot;
+            if let Ok(mut slot) = lock.lock() {
+                *slot = Some(outcome);
+            }
+            done.notify_all();
+        });
+
+        RInferenceHandle {
+            slot,
+            output_names,
+            drop_batch,
+        }
+    }
+
+    /// Wait for the outcome until `timeout` passes, or forever without one. The
+    /// wait wakes every `INTERRUPT_POLL` to let the user interrupt it, and releases
+    /// the slot while R checks, since R may run event handlers that read it.
+    fn wait_interruptibly(
+        &self,
+        timeout: Option<Duration>,
+    ) -> ChurOnResult<MutexGuard<'_, Option<ChurOnResult<AsyncOutputs>>>> {
+        let (lock, done) = &*self.slot;
+        // Deadlines past what an Instant can hold are treated as waiting forever
+        let deadline = timeout.and_then(|timeout| Instant::now().checked_add(timeout));
+        loop {
+            let outcome = lock.lock().map_err(|_| Self::poisoned())?;
+            let remaining = deadline.map(|deadline| deadline.saturating_duration_since(Instant::now()));
+            if outcome.is_some() || remaining == Some(Duration::ZERO) {
+                return Ok(outcome);
+            }
+            let poll = remaining.map_or(INTERRUPT_POLL, |remaining| remaining.min(INTERRUPT_POLL));
+            let (outcome, _) = done
+                .wait_timeout_while(outcome, poll, |outcome| outcome.is_none())
+                .map_err(|_| Self::poisoned())?;
+            if outcome.is_some() {
+                return Ok(outcome);
+            }
+            drop(outcome);
+            if user_interrupted() {
+                return Err(ChurOnError::Inference(
+                    "Interrupted while waiting for asynchronous inference".to_string(),
+                ));
+            }
+        }
+    }
+
+    fn poisoned() -> ChurOnError {
+        ChurOnError::Inference("Asynchronous inference state is unavailable".to_string())
+    }
+}
+
+/// Inputs and pre-allocated outputs bound to a session once, so tight loops can
+/// rewrite input data in place and rerun without creating new ORT values
+#[extendr]
+pub struct RBinding {
+    session: Arc<Mutex<Session>>,
+    binding: IoBinding,
+    /// Input tensors owned here and read by ORT in place on every run
+    inputs: Vec<(String, DynTensor)>,
+    output_names: Vec<String>,
+}
+
+#[extendr]
+impl RBinding {
+    pub fn get_input_names(&self) -> Vec<String> {
+        self.inputs.iter().map(|(name, _)| name.clone()).collect()
+    }
+
+    pub fn get_output_names(&self) -> Vec<String> {
+        self.output_names.clone()
+    }
+
+    /// Overwrite the data of some bound inputs, which must keep their element count
+    fn update_inputs(&mut self, inputs: List) -> extendr_api::Result<()> {
+        #[cfg(target_arch = "wasm32")]
+        {
+            let _ = inputs;
+            Ok(())
+        }
+
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+        for (input_name, robj) in inputs.iter() {
+            let (name, tensor) = self
+                .inputs
+                .iter_mut()
+                .find(|(name, _)| name.as_str() == input_name)
+                .ok_or_else(|| ChurOnError::Validation(format!("Unknown bound input: {}", input_name)))?;
+            Self::fill_tensor(name, tensor, &robj)?;
+            // CPU tensors are read in place; rebinding keeps device copies current
+            self.binding.bind_input(name.as_str(), &*tensor).map_err(|e| {
+                ChurOnError::Inference(format!("Failed to rebind input '{}': {}", name, e))
+            })?;
+        }
+        Ok(())
+        }
+    }
+
+    /// Run the session on the bound inputs, writing into the bound outputs
+    fn run(&mut self) -> extendr_api::Result<List> {
+        #[cfg(target_arch = "wasm32")]
+        {
+            Ok(List::from_values(Vec::<Robj>::new()))
+        }
+
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+        let mut session = RSession::lock_shared(&self.session)?;
+        let outputs = session
+            .run_binding(&self.binding)
+            .map_err(|e| ChurOnError::Inference(format!("Inference execution failed: {}", e)))?;
+        RSession::extract_outputs(outputs, &self.output_names, false)
+        }
+    }
+}
+
+impl RBinding {
+    /// Bind every model input to a tensor holding `inputs`, then run once to learn
+    /// the output shapes and bind outputs of those shapes. Tensor outputs are
+    /// reused by later runs; other outputs are allocated by ORT on each run.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn create(rsession: &RSession, inputs: &List) -> ChurOnResult<Self> {
+        let mut session = rsession.lock_session()?;
+        let allocator = Allocator::default();
+        let mut bound_inputs = Vec::with_capacity(session.inputs.len());
+        for input in &session.inputs {
+            let robj = inputs
+                .iter()
+                .find(|(name, _)| *name == input.name)
+                .map(|(_, robj)| robj)
+                .ok_or_else(|| {
+                    ChurOnError::Validation(format!("A binding needs every model input; '{}' is missing", input.name))
+                })?;
+            let ValueType::Tensor { ty, shape: declared, .. } = &input.input_type else {
+                return Err(ChurOnError::Validation(format!(
+                    "Input '{}' is not a tensor and cannot be bound",
+                    input.name
+                )));
+            };
+            let expected: Vec<usize> = declared.iter().map(|&x| if x == -1 { 1 } else { x as usize }).collect();
+            let shape = DataConverter::effective_shape(DataConverter::r_dims(&robj), &expected);
+            let mut tensor = DynTensor::new(&allocator, *ty, shape).map_err(|e| {
+                ChurOnError::DataConversion(format!("Failed to allocate input '{}': {}", input.name, e))
+            })?;
+            Self::fill_tensor(&input.name, &mut tensor, &robj)?;
+            bound_inputs.push((input.name.clone(), tensor));
+        }
+        if let Some((name, _)) = inputs.iter().find(|(name, _)| !bound_inputs.iter().any(|(bound, _)| bound == *name)) {
+            return Err(ChurOnError::Validation(format!("Unknown input name: {}", name)));
+        }
+
+        let output_types: Vec<ValueType> = {
+            let sizing: Vec<(String, SessionInputValue<'_>)> = bound_inputs
+                .iter()
+                .map(|(name, tensor)| (name.clone(), tensor.into()))
+                .collect();
+            let outputs = session
+                .run(sizing)
+                .map_err(|e| ChurOnError::Inference(format!("Inference execution failed: {}", e)))?;
+            rsession
+                .output_names
+                .iter()
+                .map(|name| outputs.get(name).map(|value| value.dtype().clone()))
+                .collect::<Option<_>>()
+                .ok_or_else(|| ChurOnError::Inference("Model did not produce every output".to_string()))?
+        };
+
+        let bind_error = |name: &str, e: ort::Error| {
+            ChurOnError::Inference(format!("Failed to bind '{}': {}", name, e))
+        };
+        let mut binding = session
+            .create_binding()
+            .map_err(|e| ChurOnError::Inference(format!("Failed to create binding: {}", e)))?;
+        for (name, tensor) in &bound_inputs {
+            binding.bind_input(name.as_str(), tensor).map_err(|e| bind_error(name, e))?;
+        }
+        for (name, value_type) in rsession.output_names.iter().zip(output_types) {
+            match value_type {
+                ValueType::Tensor { ty, shape, .. } => {
+                    let output = DynTensor::new(&allocator, ty, shape).map_err(|e| bind_error(name, e))?;
+                    binding.bind_output(name.as_str(), output)
+                }
+                _ => binding.bind_output_to_device(name.as_str(), &MemoryInfo::default()),
+            }
+            .map_err(|e| bind_error(name, e))?;
+        }
+        drop(session);
+
+        Ok(RBinding {
+            session: Arc::clone(rsession.shared_session()?),
+            binding,
+            inputs: bound_inputs,
+            output_names: rsession.output_names.clone(),
+        })
+    }
+
+    /// Copy R data into an existing tensor, converting to its element type
+    #[cfg(not(target_arch = "wasm32"))]
+    fn fill_tensor(name: &str, tensor: &mut DynTensor, robj: &Robj) -> ChurOnResult<()> {
+        let numeric = RNumeric::from_robj(robj).ok_or_else(|| {
+            ChurOnError::DataConversion(format!("Bound input '{}' must be numeric", name))
+        })?;
+        let tensor_error = |e: ort::Error| {
+            ChurOnError::DataConversion(format!("Failed to write bound input '{}': {}", name, e))
+        };
+        let element_type = match tensor.dtype() {
+            ValueType::Tensor { ty, .. } => *ty,
+            _ => TensorElementType::Undefined,
+        };
+        match element_type {
+            TensorElementType::Float32 => {
+                numeric.write_into(tensor.try_extract_tensor_mut::<f32>().map_err(tensor_error)?.1, |x| x as f32, |x| x as f32)
+            }
+            TensorElementType::Float64 => {
+                numeric.write_into(tensor.try_extract_tensor_mut::<f64>().map_err(tensor_error)?.1, |x| x, f64::from)
+            }
+            TensorElementType::Int32 => {
+                numeric.write_into(tensor.try_extract_tensor_mut::<i32>().map_err(tensor_error)?.1, |x| x as i32, |x| x)
+            }
+            TensorElementType::Int64 => {
+                numeric.write_into(tensor.try_extract_tensor_mut::<i64>().map_err(tensor_error)?.1, |x| x as i64, i64::from)
+            }
+            other => {
+                return Err(ChurOnError::Validation(format!(
+                    "Input '{}' has element type {} and cannot be bound",
+                    name, other
+                )))
+            }
+        }
+        .map_err(|len| {
+            ChurOnError::DataConversion(format!(
+                "Bound input '{}' holds {} elements but {} were given",
+                name,
+                len,
+                numeric.len()
+            ))
+        })
+    }
+}
+
+/// Precision in which float32 tensor outputs reach R
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputPrecision {
+    /// Widened to R doubles
+    Double,
+    /// Kept as single-precision bits; see `DataConverter::float32_to_r`
+    Float32,
+}
+
+impl OutputPrecision {
+    pub fn parse(precision: &str) -> ChurOnResult<Self> {
+        match precision {
+            "double" => Ok(OutputPrecision::Double),
+            "float32" => Ok(OutputPrecision::Float32),
+            other => Err(ChurOnError::Validation(format!(
+                "output_precision must be \"double\" or \"float32\", got \"{}\"",
+                other
+            ))),
+        }
+    }
+}
+
+/// A float32 tensor output handed to R as an ALTREP double vector. Elements are
+/// widened to double as R reads them, and the buffer is only copied into an
+/// ordinary R vector when R asks for a data pointer (for example before
+/// modifying it or passing it to C code). The ORT tensor is released once copied.
+#[cfg(not(target_arch = "wasm32"))]
+#[derive(Clone)]
+struct LazyF32Output {
+    /// Keeps the ORT tensor that `data` points into alive until it is copied
+    _value: Option<Arc<DynValue>>,
+    data: *const f32,
+    len: usize,
+    /// Start of the copy, held in the ALTREP's second data slot
+    materialized: Option<*const f64>,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+extern "C" {
+    // SEXP arguments, passed as untyped pointers
+    fn R_altrep_data1(x: *mut c_void) -> *mut c_void;
+    fn R_set_altrep_data2(x: *mut c_void, v: *mut c_void);
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+thread_local! {
+    static LAZY_F32_CLASS: Robj = Altrep::make_altreal_class::<LazyF32Output>("churon_lazy_f32", "churon");
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl LazyF32Output {
+    /// Wrap `value` if it is a float32 tensor
+    fn new(value: Arc<DynValue>) -> Option<Self> {
+        if !matches!(value.dtype(), ValueType::Tensor { ty: TensorElementType::Float32, .. }) {
+            return None;
+        }
+        let (_, data) = value.try_extract_tensor::<f32>().ok()?;
+        let (data, len) = (data.as_ptr(), data.len());
+        Some(LazyF32Output { _value: Some(value), data, len, materialized: None })
+    }
+
+    /// Elements of the tensor; only valid until `materialized` is set
+    fn as_slice(&self) -> &[f32] {
+        // SAFETY: `data` points at `len` floats owned by the tensor in `_value`,
+        // which is kept until the copy exists and is never written to
+        unsafe { std::slice::from_raw_parts(self.data, self.len) }
+    }
+
+    fn materialized(&self) -> Option<&[f64]> {
+        // SAFETY: the copy has `len` doubles and lives in the ALTREP object this
+        // state belongs to
+        self.materialized.map(|data| unsafe { std::slice::from_raw_parts(data, self.len) })
+    }
+
+    fn into_robj(self) -> Robj {
+        LAZY_F32_CLASS.with(|class| Altrep::from_state_and_class(self, class.clone(), false).into())
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl std::fmt::Debug for LazyF32Output {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "churon lazy float32 output ({} elements)", self.len)
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl AltrepImpl for LazyF32Output {
+    fn length(&self) -> usize {
+        self.len
+    }
+
+    fn dataptr(x: extendr_api::SEXP, _writeable: bool) -> *mut u8 {
+        single_threaded(|| {
+            // SAFETY: `x` is an object of this class, whose first data slot is the
+            // external pointer to its state
+            let state = unsafe {
+                let data1 = R_altrep_data1(x.cast()).cast();
+                &mut *(extendr_api::R_ExternalPtrAddr(data1) as *mut LazyF32Output)
+            };
+            if let Some(data) = state.materialized {
+                return data as *mut u8;
+            }
+            let copy: Robj = Doubles::from_values(state.as_slice().iter().map(|&v| v as f64)).into();
+            let data = copy.as_real_slice().map_or(std::ptr::null(), <[f64]>::as_ptr);
+            // SAFETY: `x` keeps the copy alive from here on
+            unsafe { R_set_altrep_data2(x.cast(), copy.get().cast()) };
+            state.materialized = Some(data);
+            state._value = None;
+            data as *mut u8
+        })
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl AltRealImpl for LazyF32Output {
+    fn elt(&self, index: usize) -> Rfloat {
+        match self.materialized() {
+            Some(copy) => Rfloat::from(copy[index]),
+            None => Rfloat::from(self.as_slice()[index] as f64),
+        }
+    }
+
+    fn get_region(&self, index: usize, data: &mut [Rfloat]) -> usize {
+        let n = data.len().min(self.len.saturating_sub(index));
+        match self.materialized() {
+            Some(copy) => {
+                for (dst, &x) in data[..n].iter_mut().zip(&copy[index..]) {
+                    *dst = Rfloat::from(x);
+                }
+            }
+            None => {
+                for (dst, &x) in data[..n].iter_mut().zip(&self.as_slice()[index..]) {
+                    *dst = Rfloat::from(x as f64);
+                }
+            }
+        }
+        n
+    }
+}
+
+/// Operator set domain of the operators churon implements itself
+const CHURON_DOMAIN: &str = "ai.churon";
+
+/// Domain of the operators onnxruntime-extensions registers
+const EXTENSIONS_DOMAIN: &str = "ai.onnx.contrib";
+
+/// Custom operator domains churon registers with sessions: its own built-in
+/// operators and those defined in R with `onnx_custom_op()`
+struct CustomOps;
+
+impl CustomOps {
+    /// Element type names accepted for custom operator inputs and outputs
+    const TYPES: [&'static str; 6] = ["float", "double", "int32", "int64", "bool", "string"];
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn element_type(name: &str) -> TensorElementType {
+        match name {
+            "float" => TensorElementType::Float32,
+            "double" => TensorElementType::Float64,
+            "int32" => TensorElementType::Int32,
+            "int64" => TensorElementType::Int64,
+            "bool" => TensorElementType::Bool,
+            "string" => TensorElementType::String,
+            _ => TensorElementType::Undefined,
+        }
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn builtin_domain() -> ort::Result<OperatorDomain> {
+        BuiltinOp::ALL
+            .into_iter()
+            .try_fold(OperatorDomain::new(CHURON_DOMAIN)?, |domain, op| domain.add(op))
+    }
+
+    /// One domain per distinct domain name of `operators`
+    #[cfg(not(target_arch = "wasm32"))]
+    fn r_domains(operators: &[ROperator]) -> ort::Result<Vec<OperatorDomain>> {
+        let mut names: Vec<&str> = Vec::new();
+        for op in operators {
+            if !names.contains(&op.domain.as_str()) {
+                names.push(&op.domain);
+            }
+        }
+        names
+            .into_iter()
+            .map(|name| {
+                operators
+                    .iter()
+                    .filter(|op| op.domain == name)
+                    .cloned()
+                    .try_fold(OperatorDomain::new(name)?, |domain, op| domain.add(op))
+            })
+            .collect()
+    }
+
+    /// Write `strings` into a string tensor a kernel was handed as output
+    #[cfg(not(target_arch = "wasm32"))]
+    fn fill_strings<S: AsRef<str>>(value: &mut DynValue, strings: &[S]) -> ort::Result<()> {
+        let copies = strings
+            .iter()
+            .map(|s| std::ffi::CString::new(s.as_ref()))
+            .collect::<std::result::Result<Vec<_>, _>>()
+            .map_err(|e| ort::Error::new(format!("String output contains a NUL byte: {}", e)))?;
+        let pointers: Vec<*const c_char> = copies.iter().map(|copy| copy.as_ptr()).collect();
+        // SAFETY: `value` is a live string tensor of `pointers.len()` elements and
+        // ORT copies the NUL-terminated strings before returning
+        unsafe {
+            ort::error::status_to_result((ort::api().FillStringTensor)(
+                value.ptr_mut(),
+                pointers.as_ptr(),
+                pointers.len(),
+            ))
+        }
+    }
+}
+
+/// Operators churon registers with every session under `CHURON_DOMAIN`, for
+/// string preprocessing that standard ONNX operators do not cover. Each takes
+/// one string tensor.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum BuiltinOp {
+    /// Lowercase each string
+    StringLower,
+    /// Uppercase each string
+    StringUpper,
+    /// Number of characters of each string, as int64
+    StringLength,
+    /// Stable FNV-1a hash of each string modulo the `num_buckets` attribute, as int64
+    StringHashBucket,
+    /// Split each string on whitespace into a new last axis, padded with empty
+    /// strings to the longest split or cut to the `max_tokens` attribute
+    WhitespaceTokenize,
+}
+
+/// Elements computed by a `BuiltinOp`
+#[derive(Debug, PartialEq)]
+enum BuiltinOutput {
+    Strings(Vec<String>),
+    Int64(Vec<i64>),
+}
+
+impl BuiltinOp {
+    const ALL: [BuiltinOp; 5] = [
+        BuiltinOp::StringLower,
+        BuiltinOp::StringUpper,
+        BuiltinOp::StringLength,
+        BuiltinOp::StringHashBucket,
+        BuiltinOp::WhitespaceTokenize,
+    ];
+
+    fn as_str(self) -> &'static str {
+        match self {
+            BuiltinOp::StringLower => "StringLower",
+            BuiltinOp::StringUpper => "StringUpper",
+            BuiltinOp::StringLength => "StringLength",
+            BuiltinOp::StringHashBucket => "StringHashBucket",
+            BuiltinOp::WhitespaceTokenize => "WhitespaceTokenize",
+        }
+    }
+
+    /// Element type name of the output, as in `CustomOps::TYPES`
+    fn output_type(self) -> &'static str {
+        match self {
+            BuiltinOp::StringLength | BuiltinOp::StringHashBucket => "int64",
+            _ => "string",
+        }
+    }
+
+    /// Integer attribute the operator reads, if any
+    fn attribute(self) -> Option<&'static str> {
+        match self {
+            BuiltinOp::StringHashBucket => Some("num_buckets"),
+            BuiltinOp::WhitespaceTokenize => Some("max_tokens"),
+            _ => None,
+        }
+    }
+
+    /// Apply to the elements of a string tensor of `shape`, returning the output
+    /// shape and elements
+    fn apply(self, shape: &[i64], strings: &[String], attribute: i64) -> std::result::Result<(Vec<i64>, BuiltinOutput), String> {
+        let output = match self {
+            BuiltinOp::StringLower => BuiltinOutput::Strings(strings.iter().map(|s| s.to_lowercase()).collect()),
+            BuiltinOp::StringUpper => BuiltinOutput::Strings(strings.iter().map(|s| s.to_uppercase()).collect()),
+            BuiltinOp::StringLength => BuiltinOutput::Int64(strings.iter().map(|s| s.chars().count() as i64).collect()),
+            BuiltinOp::StringHashBucket => {
+                if attribute <= 0 {
+                    return Err(format!("StringHashBucket needs a positive num_buckets attribute, got {}", attribute));
+                }
+                BuiltinOutput::Int64(strings.iter().map(|s| (Self::fnv1a(s) % attribute as u64) as i64).collect())
+            }
+            BuiltinOp::WhitespaceTokenize => {
+                let tokens: Vec<Vec<&str>> = strings.iter().map(|s| s.split_whitespace().collect()).collect();
+                let width = if attribute > 0 {
+                    attribute as usize
+                } else {
+                    tokens.iter().map(Vec::len).max().unwrap_or(0)
+                };
+                let padded = tokens
+                    .iter()
+                    .flat_map(|row| (0..width).map(move |i| row.get(i).copied().unwrap_or("").to_string()))
+                    .collect();
+                let mut shape = shape.to_vec();
+                shape.push(width as i64);
+                return Ok((shape, BuiltinOutput::Strings(padded)));
+            }
+        };
+        Ok((shape.to_vec(), output))
+    }
+
+    /// 64-bit FNV-1a hash of the string's UTF-8 bytes, the same on every platform
+    fn fnv1a(s: &str) -> u64 {
+        s.bytes()
+            .fold(0xcbf29ce484222325, |hash, byte| (hash ^ u64::from(byte)).wrapping_mul(0x100000001b3))
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl Operator for BuiltinOp {
+    fn name(&self) -> &str {
+        self.as_str()
+    }
+
+    fn inputs(&self) -> Vec<OperatorInput> {
+        vec![OperatorInput::required(TensorElementType::String)]
+    }
+
+    fn outputs(&self) -> Vec<OperatorOutput> {
+        vec![OperatorOutput::required(CustomOps::element_type(self.output_type()))]
+    }
+
+    fn create_kernel(&self, attributes: &KernelAttributes) -> ort::Result<Box<dyn Kernel>> {
+        let op = *self;
+        let attribute = op.attribute().and_then(|name| attributes.get::<i64>(name)).unwrap_or(0);
+        Ok(Box::new(move |ctx: &KernelContext| {
+            let input = ctx
+                .input(0)?
+                .ok_or_else(|| ort::Error::new(format!("{} is missing its input", op.as_str())))?;
+            let (shape, strings) = input.try_extract_strings()?;
+            let (shape, output) = op.apply(shape, &strings, attribute).map_err(ort::Error::new)?;
+            let mut value = ctx
+                .output(0, shape)?
+                .ok_or_else(|| ort::Error::new(format!("{} is missing its output", op.as_str())))?;
+            match output {
+                BuiltinOutput::Strings(strings) => CustomOps::fill_strings(&mut value, &strings),
+                BuiltinOutput::Int64(data) => {
+                    value.try_extract_tensor_mut::<i64>()?.1.copy_from_slice(&data);
+                    Ok(())
+                }
+            }
+        }))
+    }
+}
+
+/// An R function called by a custom operator kernel. R objects may only be
+/// touched on the R main thread, so kernels refuse to run on other threads and
+/// a function released on one is leaked instead.
+struct RFunction {
+    function: std::mem::ManuallyDrop<Robj>,
+    thread: std::thread::ThreadId,
+}
+
+// SAFETY: the R function is only called and released on `thread`, the R main
+// thread it was created on
+unsafe impl Send for RFunction {}
+unsafe impl Sync for RFunction {}
+
+impl RFunction {
+    fn new(function: Robj) -> Self {
+        RFunction {
+            function: std::mem::ManuallyDrop::new(function),
+            thread: std::thread::current().id(),
+        }
+    }
+
+    fn on_r_thread(&self) -> bool {
+        std::thread::current().id() == self.thread
+    }
+
+    fn call(&self, args: Vec<Robj>) -> extendr_api::Result<Robj> {
+        self.function.call(Pairlist::from_pairs(args.into_iter().map(|arg| ("", arg))))
+    }
+}
+
+impl Drop for RFunction {
+    fn drop(&mut self) {
+        if self.on_r_thread() {
+            // SAFETY: dropped once, here
+            unsafe { std::mem::ManuallyDrop::drop(&mut self.function) }
+        }
+    }
+}
+
+impl PartialEq for RFunction {
+    fn eq(&self, other: &Self) -> bool {
+        // SAFETY: only the SEXP addresses are compared; neither object is dereferenced
+        unsafe { self.function.get() == other.function.get() }
+    }
+}
+
+impl fmt::Debug for RFunction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("RFunction")
+    }
+}
+
+thread_local! {
+    /// Set while an R kernel runs on this thread
+    static IN_R_KERNEL: Cell<bool> = const { Cell::new(false) };
+}
+
+/// A custom operator whose kernel is an R function, as defined by `onnx_custom_op()`
+#[derive(Debug, Clone, PartialEq)]
+struct ROperator {
+    domain: String,
+    name: String,
+    /// Element type names of the inputs and outputs, as in `CustomOps::TYPES`
+    inputs: Vec<String>,
+    outputs: Vec<String>,
+    /// Called with the inputs; returns `list(value, error)`, where `value` is the
+    /// output or a list of the outputs
+    kernel: Arc<RFunction>,
+}
+
+impl ROperator {
+    /// Parse one of the operator lists built by `onnx_custom_op()`
+    fn from_list(op: &List) -> ChurOnResult<Self> {
+        let field = |name: &str| op.iter().find(|(n, _)| *n == name).map(|(_, value)| value);
+        let string = |name: &str| {
+            field(name)
+                .and_then(|value| value.as_str().map(str::to_string))
+                .ok_or_else(|| ChurOnError::Validation(format!("Custom operator field '{}' must be a string", name)))
+        };
+        let types = |name: &str| -> ChurOnResult<Vec<String>> {
+            let types: Vec<String> = field(name)
+                .and_then(|value| value.as_str_vector().map(|v| v.iter().map(|s| s.to_string()).collect()))
+                .ok_or_else(|| ChurOnError::Validation(format!("Custom operator field '{}' must be a character vector", name)))?;
+            match types.iter().find(|ty| !CustomOps::TYPES.contains(&ty.as_str())) {
+                Some(ty) => Err(ChurOnError::Validation(format!(
+                    "Unsupported custom operator element type '{}'; use one of {}",
+                    ty,
+                    CustomOps::TYPES.join(", ")
+                ))),
+                None => Ok(types),
+            }
+        };
+        let domain = string("domain")?;
+        if domain == CHURON_DOMAIN {
+            return Err(ChurOnError::Validation(format!(
+                "The '{}' domain is reserved for churon's built-in operators",
+                CHURON_DOMAIN
+            )));
+        }
+        let kernel = field("kernel")
+            .filter(|kernel| kernel.is_function())
+            .ok_or_else(|| ChurOnError::Validation("Custom operator field 'kernel' must be a function".to_string()))?;
+        Ok(ROperator {
+            domain,
+            name: string("name")?,
+            inputs: types("inputs")?,
+            outputs: types("outputs")?,
+            kernel: Arc::new(RFunction::new(kernel)),
+        })
+    }
+
+    fn qualified_name(&self) -> String {
+        format!("{}:{}", self.domain, self.name)
+    }
+
+    /// Whether an R kernel is running on this thread
+    fn in_kernel() -> bool {
+        IN_R_KERNEL.with(Cell::get)
+    }
+
+    /// Call the R function with the kernel's inputs and write what it returns
+    /// into the outputs
+    #[cfg(not(target_arch = "wasm32"))]
+    fn compute(&self, ctx: &KernelContext) -> ort::Result<()> {
+        if !self.kernel.on_r_thread() {
+            return Err(ort::Error::new(format!(
+                "R custom operator {} can only run on the R main thread; use onnx_run() rather than an asynchronous or parallel run",
+                self.qualified_name()
+            )));
+        }
+        let mut args = Vec::with_capacity(self.inputs.len());
+        for index in 0..ctx.num_inputs()? {
+            let input = ctx.input(index)?.ok_or_else(|| {
+                ort::Error::new(format!("R custom operator {} is missing input {}", self.qualified_name(), index + 1))
+            })?;
+            args.push(DataConverter::value_to_r(&self.name, &input).map_err(|e| ort::Error::new(e.to_string()))?);
+        }
+        let failed = |message: String| ort::Error::new(format!("R custom operator {} failed: {}", self.qualified_name(), message));
+        let outer = IN_R_KERNEL.with(|flag| flag.replace(true));
+        let result = self.kernel.call(args);
+        IN_R_KERNEL.with(|flag| flag.set(outer));
+        let result = result.and_then(List::try_from).map_err(|e| failed(e.to_string()))?;
+        if let Some(message) = result.elt(1).ok().and_then(|error| error.as_str().map(str::to_string)) {
+            return Err(failed(message));
+        }
+        let value = result.elt(0).map_err(|e| failed(e.to_string()))?;
+        let values: Vec<Robj> = if self.outputs.len() == 1 {
+            vec![value]
+        } else {
+            value
+                .as_list()
+                .filter(|list| list.len() == self.outputs.len())
+                .ok_or_else(|| failed(format!("expected a list of {} outputs", self.outputs.len())))?
+                .values()
+                .collect()
+        };
+        for (index, (ty, robj)) in self.outputs.iter().zip(&values).enumerate() {
+            self.write_output(ctx, index, ty, robj)?;
+        }
+        Ok(())
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn write_output(&self, ctx: &KernelContext, index: usize, ty: &str, robj: &Robj) -> ort::Result<()> {
+        let mismatch = || {
+            ort::Error::new(format!(
+                "Output {} of R custom operator {} must be a {} vector or array",
+                index + 1,
+                self.qualified_name(),
+                if ty == "string" { "character" } else if ty == "bool" { "logical" } else { "numeric" }
+            ))
+        };
+        let shape: Vec<i64> = DataConverter::r_dims(robj).into_iter().map(|d| d as i64).collect();
+        let mut value = ctx.output(index, shape)?.ok_or_else(|| {
+            ort::Error::new(format!("R custom operator {} is missing output {}", self.qualified_name(), index + 1))
+        })?;
+        match CustomOps::element_type(ty) {
+            TensorElementType::String => {
+                let strings = robj.as_str_vector().ok_or_else(mismatch)?;
+                CustomOps::fill_strings(&mut value, &strings)
+            }
+            TensorElementType::Bool => {
+                let data = robj.as_logical_slice().ok_or_else(mismatch)?;
+                let dst = value.try_extract_tensor_mut::<bool>()?.1;
+                if data.len() != dst.len() {
+                    return Err(mismatch());
+                }
+                dst.iter_mut().zip(data).for_each(|(d, x)| *d = x.is_true());
+                Ok(())
+            }
+            element_type => {
+                let numeric = RNumeric::from_robj(robj).ok_or_else(mismatch)?;
+                match element_type {
+                    TensorElementType::Float32 => {
+                        numeric.write_into(value.try_extract_tensor_mut::<f32>()?.1, |x| x as f32, |x| x as f32)
+                    }
+                    TensorElementType::Float64 => {
+                        numeric.write_into(value.try_extract_tensor_mut::<f64>()?.1, |x| x, f64::from)
+                    }
+                    TensorElementType::Int32 => {
+                        numeric.write_into(value.try_extract_tensor_mut::<i32>()?.1, |x| x as i32, |x| x)
+                    }
+                    _ => numeric.write_into(value.try_extract_tensor_mut::<i64>()?.1, |x| x as i64, i64::from),
+                }
+                .map_err(|_| mismatch())
+            }
+        }
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl Operator for ROperator {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn inputs(&self) -> Vec<OperatorInput> {
+        self.inputs
+            .iter()
+            .map(|ty| OperatorInput::required(CustomOps::element_type(ty)))
+            .collect()
+    }
+
+    fn outputs(&self) -> Vec<OperatorOutput> {
+        self.outputs
+            .iter()
+            .map(|ty| OperatorOutput::required(CustomOps::element_type(ty)))
+            .collect()
+    }
+
+    fn create_kernel(&self, _: &KernelAttributes) -> ort::Result<Box<dyn Kernel>> {
+        let op = self.clone();
+        Ok(Box::new(move |ctx: &KernelContext| op.compute(ctx)))
+    }
+}
+
+/// The data of an R double or integer vector
+#[derive(Debug, Clone, Copy)]
+enum RNumeric<'a> {
+    Doubles(&'a [f64]),
+    Integers(&'a [i32]),
+}
+
+impl<'a> RNumeric<'a> {
+    fn from_robj(robj: &'a Robj) -> Option<Self> {
+        robj.as_real_slice()
+            .map(RNumeric::Doubles)
+            .or_else(|| robj.as_integer_slice().map(RNumeric::Integers))
+    }
+
+    fn len(&self) -> usize {
+        match self {
+            RNumeric::Doubles(data) => data.len(),
+            RNumeric::Integers(data) => data.len(),
+        }
+    }
+
+    /// Convert in a single pass, with no intermediate array
+    fn collect<T>(&self, from_f64: impl Fn(f64) -> T, from_i32: impl Fn(i32) -> T) -> Vec<T> {
+        match self {
+            RNumeric::Doubles(data) => data.iter().map(|&x| from_f64(x)).collect(),
+            RNumeric::Integers(data) => data.iter().map(|&x| from_i32(x)).collect(),
+        }
+    }
+
+    /// Convert into an existing buffer of the same length, returning the buffer's
+    /// length when it differs
+    fn write_into<T>(&self, dst: &mut [T], from_f64: impl Fn(f64) -> T, from_i32: impl Fn(i32) -> T) -> std::result::Result<(), usize> {
+        if dst.len() != self.len() {
+            return Err(dst.len());
+        }
+        match self {
+            RNumeric::Doubles(data) => dst.iter_mut().zip(data.iter()).for_each(|(d, &x)| *d = from_f64(x)),
+            RNumeric::Integers(data) => dst.iter_mut().zip(data.iter()).for_each(|(d, &x)| *d = from_i32(x)),
+        }
+        Ok(())
+    }
+
+    /// Session input for a tensor of `element_type`, borrowing R's buffer when its
+    /// storage already matches. `None` for element types only the prepared path handles.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn to_session_value(
+        self,
+        input_name: &str,
+        element_type: TensorElementType,
+        shape: Vec<usize>,
+    ) -> ChurOnResult<Option<SessionInputValue<'a>>> {
+        let tensor_error = |e: ort::Error| {
+            ChurOnError::DataConversion(format!(
+                "Failed to create tensor for input '{}': {}",
+                input_name, e
+            ))
+        };
+        let value = match (element_type, self) {
+            (TensorElementType::Float64, RNumeric::Doubles(data)) => {
+                TensorRef::from_array_view((shape, data)).map_err(tensor_error)?.into()
+            }
+            (TensorElementType::Int32, RNumeric::Integers(data)) => {
+                TensorRef::from_array_view((shape, data)).map_err(tensor_error)?.into()
+            }
+            (TensorElementType::Float32, _) => {
+                Self::owned(shape, self.collect(|x| x as f32, |x| x as f32)).map_err(tensor_error)?
+            }
+            (TensorElementType::Float64, _) => {
+                Self::owned(shape, self.collect(|x| x, f64::from)).map_err(tensor_error)?
+            }
+            (TensorElementType::Int32, _) => {
+                Self::owned(shape, self.collect(|x| x as i32, |x| x)).map_err(tensor_error)?
+            }
+            (TensorElementType::Int64, _) => {
+                Self::owned(shape, self.collect(|x| x as i64, i64::from)).map_err(tensor_error)?
+            }
+            _ => return Ok(None),
+        };
+        Ok(Some(value))
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn owned<T: PrimitiveTensorElementType + fmt::Debug + Clone + 'static>(
+        shape: Vec<usize>,
+        data: Vec<T>,
+    ) -> ort::Result<SessionInputValue<'a>> {
+        Ok(Tensor::from_array((shape, data))?.into())
+    }
+}
+
+pub struct DataConverter;
+
+impl DataConverter {
+    /// Convert R object (vector, matrix, array) to ndarray f32
+    pub fn r_obj_to_ndarray_f32(
+        robj: &Robj,
+        expected_shape: &[usize],
+    ) -> ChurOnResult<ArrayD<f32>> {
+        // Get dimensions from R object
+        let actual_shape = Self::r_dims(robj);
+
+        let effective_shape = Self::effective_shape(actual_shape, expected_shape);
+
+        // Extract numeric data as f64 first, then convert to f32
+        let data_f32: Vec<f32> = if let Some(doubles) = robj.as_real_slice() {
+            doubles.iter().map(|&x| x as f32).collect()
+        } else if let Some(ints) = robj.as_integer_slice() {
+            ints.iter().map(|&x| x as f32).collect()
+        } else {
+            return Err(ChurOnError::DataConversion(
+                "Input must be numeric (integer or real)".to_string(),
+            ));
+        };
+
+        Self::check_element_count(data_f32.len(), &effective_shape)?;
+
+        ArrayD::from_shape_vec(IxDyn(&effective_shape), data_f32)
+            .map_err(|e| ChurOnError::DataConversion(format!("Failed to create ndarray: {}", e)))
+    }
+
+    /// Convert an R numeric object into an integer tensor without passing through
+    /// f32, so large token ids stay exact
+    pub fn r_obj_to_ndarray_i64(
+        robj: &Robj,
+        expected_shape: &[usize],
+    ) -> ChurOnResult<ArrayD<i64>> {
+        let effective_shape = Self::effective_shape(Self::r_dims(robj), expected_shape);
+
+        let data: Vec<i64> = if let Some(ints) = robj.as_integer_slice() {
+            ints.iter().map(|&x| x as i64).collect()
+        } else if let Some(doubles) = robj.as_real_slice() {
+            doubles.iter().map(|&x| x as i64).collect()
+        } else {
+            return Err(ChurOnError::DataConversion(
+                "Input must be numeric (integer or real)".to_string(),
+            ));
+        };
+
+        Self::check_element_count(data.len(), &effective_shape)?;
+
+        ArrayD::from_shape_vec(IxDyn(&effective_shape), data)
+            .map_err(|e| ChurOnError::DataConversion(format!("Failed to create ndarray: {}", e)))
+    }
+
+    /// Shape a numeric input takes: the R dimensions when the declared shape has
+    /// dynamic or unit dimensions, and the declared shape otherwise
+    pub fn effective_shape(actual_shape: Vec<usize>, expected_shape: &[usize]) -> Vec<usize> {
+        if expected_shape.iter().any(|&x| x == 0 || x == 1) {
+            actual_shape
+        } else {
+            expected_shape.to_vec()
+        }
+    }
+
+    pub fn check_element_count(len: usize, shape: &[usize]) -> ChurOnResult<()> {
+        let total_elements: usize = shape.iter().product();
+        if len != total_elements {
+            return Err(ChurOnError::DataConversion(format!(
+                "Data length {} doesn't match expected shape {:?} (expected {} elements)",
+                len, shape, total_elements
+            )));
+        }
+        Ok(())
+    }
+
+    pub fn ndarray_f32_to_r(array: ArrayD<f32>) -> ChurOnResult<Doubles> {
+        let data: Vec<f64> = array.iter().map(|&x| x as f64).collect();
+        Ok(Doubles::from_values(data))
+    }
+
+    pub fn ndarray_f64_to_r(array: ArrayD<f64>) -> ChurOnResult<Doubles> {
+        let data: Vec<f64> = array.iter().cloned().collect();
+        Ok(Doubles::from_values(data))
+    }
+
+    /// Dimensions of an R object, treating plain vectors as rank one
+    pub fn r_dims(robj: &Robj) -> Vec<usize> {
+        if let Some(dims) = robj.dim() {
+            dims.iter().map(|d| d.inner() as usize).collect()
+        } else {
+            vec![robj.len()]
+        }
+    }
+
+    /// Convert an R list or named vector into sequence/map input data for a declared type
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn r_obj_to_input_data(
+        input_name: &str,
+        robj: &Robj,
+        value_type: &ValueType,
+    ) -> ChurOnResult<InputData> {
+        match value_type {
+            ValueType::Sequence(element_type) => {
+                let list = robj.as_list().ok_or_else(|| {
+                    ChurOnError::DataConversion(format!(
+                        "Input '{}' expects a sequence and must be an R list",
+                        input_name
+                    ))
+                })?;
+                if list.is_empty() {
+                    return Err(ChurOnError::DataConversion(format!(
+                        "Sequence input '{}' must contain at least one element",
+                        input_name
+                    )));
+                }
+                let elements = list
+                    .values()
+                    .map(|element| Self::r_obj_to_input_data(input_name, &element, element_type))
+                    .collect::<ChurOnResult<Vec<_>>>()?;
+                Ok(InputData::Sequence(elements))
+            }
+            ValueType::Map { .. } => {
+                let keys: Vec<String> = robj
+                    .names()
+                    .ok_or_else(|| {
+                        ChurOnError::DataConversion(format!(
+                            "Input '{}' expects a map and must be a named numeric vector",
+                            input_name
+                        ))
+                    })?
+                    .map(|key| key.to_string())
+                    .collect();
+                let values: Vec<f64> = if let Some(doubles) = robj.as_real_slice() {
+                    doubles.to_vec()
+                } else if let Some(ints) = robj.as_integer_slice() {
+                    ints.iter().map(|&x| x as f64).collect()
+                } else {
+                    return Err(ChurOnError::DataConversion(format!(
+                        "Map input '{}' must have numeric values",
+                        input_name
+                    )));
+                };
+                Ok(InputData::Map(keys.into_iter().zip(values).collect()))
+            }
+            _ => {
+                if robj.is_string() {
+                    let strs = robj.as_str_vector().unwrap_or_default();
+                    Ok(InputData::Strings(strs.iter().map(|s| s.to_string()).collect()))
+                } else {
+                    let tensor = Self::r_obj_to_ndarray_f32(robj, &Self::r_dims(robj))?;
+                    Ok(InputData::Numeric(tensor))
+                }
+            }
+        }
+    }
+
+    /// Build an ONNX Runtime value from prepared input data
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn input_data_to_value(
+        input_name: &str,
+        data: &InputData,
+        value_type: &ValueType,
+    ) -> ChurOnResult<DynValue> {
+        match data {
+            InputData::Numeric(tensor) => {
+                let shape: Vec<usize> = tensor.shape().to_vec();
+                let tensor_error = |e: ort::Error| {
+                    ChurOnError::DataConversion(format!(
+                        "Failed to create tensor for input '{}': {}",
+                        input_name, e
+                    ))
+                };
+                // Integer inputs such as token ids and attention masks keep the declared type
+                let value = match value_type {
+                    ValueType::Tensor { ty: TensorElementType::Int64, .. } => {
+                        let data: Vec<i64> = tensor.iter().map(|&x| x as i64).collect();
+                        Tensor::from_array((shape, data)).map_err(tensor_error)?.into_dyn()
+                    }
+                    ValueType::Tensor { ty: TensorElementType::Int32, .. } => {
+                        let data: Vec<i32> = tensor.iter().map(|&x| x as i32).collect();
+                        Tensor::from_array((shape, data)).map_err(tensor_error)?.into_dyn()
+                    }
+                    ValueType::Tensor { ty: TensorElementType::Float64, .. } => {
+                        let data: Vec<f64> = tensor.iter().map(|&x| x as f64).collect();
+                        Tensor::from_array((shape, data)).map_err(tensor_error)?.into_dyn()
+                    }
+                    _ => {
+                        let data: Vec<f32> = tensor.iter().cloned().collect();
+                        Tensor::from_array((shape, data)).map_err(tensor_error)?.into_dyn()
+                    }
+                };
+                Ok(value)
+            }
+            InputData::Integer(tensor) => {
+                let shape: Vec<usize> = tensor.shape().to_vec();
+                let tensor_error = |e: ort::Error| {
+                    ChurOnError::DataConversion(format!(
+                        "Failed to create tensor for input '{}': {}",
+                        input_name, e
+                    ))
+                };
+                let value = match value_type {
+                    ValueType::Tensor { ty: TensorElementType::Int32, .. } => {
+                        let data: Vec<i32> = tensor.iter().map(|&x| x as i32).collect();
+                        Tensor::from_array((shape, data)).map_err(tensor_error)?.into_dyn()
+                    }
+                    _ => {
+                        let data: Vec<i64> = tensor.iter().copied().collect();
+                        Tensor::from_array((shape, data)).map_err(tensor_error)?.into_dyn()
+                    }
+                };
+                Ok(value)
+            }
+            InputData::Strings(string_data) => {
+                let shape = [string_data.len()];
+                let ort_tensor =
+                    Tensor::from_string_array((shape, string_data.as_slice())).map_err(|e| {
+                        ChurOnError::DataConversion(format!(
+                            "Failed to create string tensor for input '{}': {}",
+                            input_name, e
+                        ))
+                    })?;
+                Ok(ort_tensor.into_dyn())
+            }
+            InputData::Sequence(elements) => {
+                let element_type = match value_type {
+                    ValueType::Sequence(element_type) => element_type.as_ref(),
+                    other => other,
+                };
+                let values = elements
+                    .iter()
+                    .map(|element| Self::input_data_to_value(input_name, element, element_type))
+                    .collect::<ChurOnResult<Vec<_>>>()?;
+                let sequence = Sequence::<DynValueTypeMarker>::new(values).map_err(|e| {
+                    ChurOnError::DataConversion(format!(
+                        "Failed to create sequence for input '{}': {}",
+                        input_name, e
+                    ))
+                })?;
+                Ok(sequence.into_dyn())
+            }
+            InputData::Map(pairs) => match value_type {
+                ValueType::Map { key, value } => Self::pairs_to_map(input_name, pairs, *key, *value),
+                other => Err(ChurOnError::DataConversion(format!(
+                    "Input '{}' was given a named vector but the model expects {}",
+                    input_name, other
+                ))),
+            },
+        }
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn pairs_to_map(
+        input_name: &str,
+        pairs: &[(String, f64)],
+        key_type: TensorElementType,
+        value_type: TensorElementType,
+    ) -> ChurOnResult<DynValue> {
+        let map_error = |e: ort::Error| {
+            ChurOnError::DataConversion(format!(
+                "Failed to create map for input '{}': {}",
+                input_name, e
+            ))
+        };
+        let string_keys = || pairs.iter().map(|(k, v)| (k.clone(), *v));
+        let int_keys = || {
+            pairs
+                .iter()
+                .map(|(k, v)| {
+                    k.parse::<i64>().map(|k| (k, *v)).map_err(|_| {
+                        ChurOnError::DataConversion(format!(
+                            "Map key '{}' for input '{}' is not an integer",
+                            k, input_name
+                        ))
+                    })
+                })
+                .collect::<ChurOnResult<Vec<_>>>()
+        };
+
+        let value = match (key_type, value_type) {
+            (TensorElementType::String, TensorElementType::Float32) => {
+                Map::<String, f32>::new(string_keys().map(|(k, v)| (k, v as f32)))
+                    .map_err(map_error)?
+                    .into_dyn()
+            }
+            (TensorElementType::String, TensorElementType::Float64) => {
+                Map::<String, f64>::new(string_keys()).map_err(map_error)?.into_dyn()
+            }
+            (TensorElementType::String, TensorElementType::Int64) => {
+                Map::<String, i64>::new(string_keys().map(|(k, v)| (k, v as i64)))
+                    .map_err(map_error)?
+                    .into_dyn()
+            }
+            (TensorElementType::Int64, TensorElementType::Float32) => {
+                Map::<i64, f32>::new(int_keys()?.into_iter().map(|(k, v)| (k, v as f32)))
+                    .map_err(map_error)?
+                    .into_dyn()
+            }
+            (TensorElementType::Int64, TensorElementType::Float64) => {
+                Map::<i64, f64>::new(int_keys()?).map_err(map_error)?.into_dyn()
+            }
+            (TensorElementType::Int64, TensorElementType::Int64) => {
+                Map::<i64, i64>::new(int_keys()?.into_iter().map(|(k, v)| (k, v as i64)))
+                    .map_err(map_error)?
+                    .into_dyn()
+            }
+            (key, value) => {
+                return Err(ChurOnError::DataConversion(format!(
+                    "Unsupported map type map({}, {}) for input '{}'",
+                    key, value, input_name
+                )))
+            }
+        };
+        Ok(value)
+    }
+
+    /// Convert an ONNX Runtime value (tensor, sequence or map) into an R object
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn value_to_r(output_name: &str, value: &DynValue) -> ChurOnResult<Robj> {
+        match value.dtype() {
+            ValueType::Tensor { ty, .. } => Self::tensor_to_r(output_name, value, *ty),
+            ValueType::Sequence(_) => {
+                // Sequences become R lists, one element per item
+                let allocator = Allocator::default();
+                let elements = value
+                    .try_extract_sequence::<DynValueTypeMarker>(&allocator)
+                    .map_err(|e| {
+                        ChurOnError::DataConversion(format!(
+                            "Failed to extract sequence output '{}': {}",
+                            output_name, e
+                        ))
+                    })?;
+                let converted = elements
+                    .iter()
+                    .map(|element| Self::value_to_r(output_name, element))
+                    .collect::<ChurOnResult<Vec<_>>>()?;
+                Ok(List::from_values(converted).into_robj())
+            }
+            ValueType::Map { key, value: value_type } => {
+                Self::map_to_r(output_name, value, *key, *value_type)
+            }
+            other => Err(ChurOnError::DataConversion(format!(
+                "Unsupported output type {} for '{}'",
+                other, output_name
+            ))),
+        }
+    }
+
+    /// Convert a whole tensor into a flat R vector. Every element type goes
+    /// through `tensor_slice_to_r`, so dimensions are handled the same way for
+    /// all of them: none here, and `drop_batch_axis` sets them under auto_batch.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn tensor_to_r(
+        output_name: &str,
+        value: &DynValue,
+        element_type: TensorElementType,
+    ) -> ChurOnResult<Robj> {
+        let len = value.dtype().tensor_shape().map(|shape| shape.num_elements()).unwrap_or(0);
+        Self::tensor_slice_to_r(output_name, value, element_type, 0..len)
+    }
+
+    /// Convert a contiguous range of a tensor's elements into an R vector
+    #[cfg(not(target_arch = "wasm32"))]
+    fn tensor_slice_to_r(
+        output_name: &str,
+        value: &DynValue,
+        element_type: TensorElementType,
+        range: Range<usize>,
+    ) -> ChurOnResult<Robj> {
+        let extract_error = |e: ort::Error| {
+            ChurOnError::DataConversion(format!(
+                "Failed to extract output '{}': {}",
+                output_name, e
+            ))
+        };
+        let robj = match element_type {
+            TensorElementType::Float32 => {
+                let (_, data) = value.try_extract_tensor::<f32>().map_err(extract_error)?;
+                Doubles::from_values(data[range].iter().map(|&x| x as f64)).into_robj()
+            }
+            TensorElementType::Float64 => {
+                let (_, data) = value.try_extract_tensor::<f64>().map_err(extract_error)?;
+                Doubles::from_values(data[range].iter().copied()).into_robj()
+            }
+            TensorElementType::Int32 => {
+                let (_, data) = value.try_extract_tensor::<i32>().map_err(extract_error)?;
+                Integers::from_values(data[range].iter().copied()).into_robj()
+            }
+            TensorElementType::Int64 => {
+                // R integers are 32-bit, so 64-bit labels are returned as doubles
+                let (_, data) = value.try_extract_tensor::<i64>().map_err(extract_error)?;
+                Doubles::from_values(data[range].iter().map(|&x| x as f64)).into_robj()
+            }
+            TensorElementType::Bool => {
+                let (_, data) = value.try_extract_tensor::<bool>().map_err(extract_error)?;
+                Logicals::from_values(data[range].iter().copied()).into_robj()
+            }
+            TensorElementType::String => {
+                let (_, data) = value.try_extract_strings().map_err(extract_error)?;
+                Strings::from_values(&data[range]).into_robj()
+            }
+            _ => {
+                return Err(ChurOnError::DataConversion(format!(
+                    "Unsupported output data type for '{}'",
+                    output_name
+                )))
+            }
+        };
+        Ok(robj)
+    }
+
+    /// Split a batched output into one R object per example. Tensors are split along
+    /// their leading axis and sequences by element; outputs without a batch axis are
+    /// repeated for every example. With a sequence layout, tensors whose second axis
+    /// is the padded length keep only each example's real steps.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn split_value_to_r(
+        output_name: &str,
+        value: &DynValue,
+        batch_len: usize,
+        layout: Option<&SequenceLayout>,
+    ) -> ChurOnResult<Vec<Robj>> {
+        match value.dtype() {
+            ValueType::Tensor { ty, shape, .. } if shape.first() == Some(&(batch_len as i64)) => {
+                let stride: usize = shape[1..].iter().map(|&d| d as usize).product();
+                match layout {
+                    Some(layout) if shape.get(1) == Some(&(layout.padded_len as i64)) => {
+                        let step: usize = shape[2..].iter().map(|&d| d as usize).product();
+                        (0..batch_len)
+                            .map(|i| {
+                                let steps = layout.steps(i);
+                                let range = i * stride + steps.start * step..i * stride + steps.end * step;
+                                Self::tensor_slice_to_r(output_name, value, *ty, range)
+                            })
+                            .collect()
+                    }
+                    _ => (0..batch_len)
+                        .map(|i| Self::tensor_slice_to_r(output_name, value, *ty, i * stride..(i + 1) * stride))
+                        .collect(),
+                }
+            }
+            ValueType::Sequence(_) => {
+                let allocator = Allocator::default();
+                let elements = value
+                    .try_extract_sequence::<DynValueTypeMarker>(&allocator)
+                    .map_err(|e| {
+                        ChurOnError::DataConversion(format!(
+                            "Failed to extract sequence output '{}': {}",
+                            output_name, e
+                        ))
+                    })?;
+                if elements.len() == batch_len {
+                    elements
+                        .iter()
+                        .map(|element| Self::value_to_r(output_name, element))
+                        .collect()
+                } else {
+                    let whole = Self::value_to_r(output_name, value)?;
+                    Ok(vec![whole; batch_len])
+                }
+            }
+            _ => {
+                let whole = Self::value_to_r(output_name, value)?;
+                Ok(vec![whole; batch_len])
+            }
+        }
+    }
+
+    /// Bytes of tensor data a value of this type holds; zero for strings,
+    /// sequences and maps
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn value_bytes(value_type: &ValueType) -> usize {
+        match value_type {
+            ValueType::Tensor { ty, shape, .. } => ty.byte_size(shape.num_elements()),
+            _ => 0,
+        }
+    }
+
+    /// Build a named R list from owned output values given in `output_names` order
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn values_to_r_list(
+        output_names: &[String],
+        values: &[DynValue],
+        drop_batch: bool,
+    ) -> ChurOnResult<Robj> {
+        let mut r_outputs = Vec::with_capacity(values.len());
+        for (output_name, value) in output_names.iter().zip(values) {
+            let mut r_data = Self::value_to_r(output_name, value)?;
+            if drop_batch {
+                Self::drop_batch_axis(output_name, &mut r_data, value)?;
+            }
+            r_outputs.push(r_data);
+        }
+        List::from_names_and_values(output_names, r_outputs)
+            .map(|list| list.into_robj())
+            .map_err(|e| ChurOnError::DataConversion(format!("Failed to build result list: {}", e)))
+    }
+
+    /// Like `values_to_r_list`, but float32 tensors are kept in single precision
+    /// (`precision`) or become lazy ALTREP vectors that keep the ORT buffer alive
+    /// instead of being copied (`lazy`)
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn owned_values_to_r_list(
+        output_names: &[String],
+        values: Vec<DynValue>,
+        drop_batch: bool,
+        lazy: bool,
+        precision: OutputPrecision,
+    ) -> ChurOnResult<Robj> {
+        let mut r_outputs = Vec::with_capacity(values.len());
+        for (output_name, value) in output_names.iter().zip(values) {
+            let value = Arc::new(value);
+            let single = match precision {
+                OutputPrecision::Float32 => Self::float32_to_r(output_name, &value)?,
+                OutputPrecision::Double => None,
+            };
+            let mut r_data = match single {
+                Some(r_data) => r_data,
+                None => match lazy.then(|| LazyF32Output::new(Arc::clone(&value))).flatten() {
+                    Some(lazy) => lazy.into_robj(),
+                    None => Self::value_to_r(output_name, &value)?,
+                },
+            };
+            if drop_batch {
+                Self::drop_batch_axis(output_name, &mut r_data, &value)?;
+            }
+            r_outputs.push(r_data);
+        }
+        List::from_names_and_values(output_names, r_outputs)
+            .map(|list| list.into_robj())
+            .map_err(|e| ChurOnError::DataConversion(format!("Failed to build result list: {}", e)))
+    }
+
+    /// A float32 tensor as an R integer vector of class `churon_float32` holding the
+    /// raw IEEE bits, the same storage the float package's `float32` class uses.
+    /// Returns `None` for other values.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn float32_to_r(output_name: &str, value: &DynValue) -> ChurOnResult<Option<Robj>> {
+        if !matches!(value.dtype(), ValueType::Tensor { ty: TensorElementType::Float32, .. }) {
+            return Ok(None);
+        }
+        let (_, data) = value.try_extract_tensor::<f32>().map_err(|e| {
+            ChurOnError::DataConversion(format!("Failed to extract output '{}': {}", output_name, e))
+        })?;
+        let mut robj = Integers::from_values(data.iter().map(|x| x.to_bits() as i32)).into_robj();
+        robj.set_class(["churon_float32"]).map_err(|e| {
+            ChurOnError::DataConversion(format!("Failed to set class of '{}': {}", output_name, e))
+        })?;
+        Ok(Some(robj))
+    }
+
+    /// An R error condition recording why a single batch item failed
+    pub fn batch_error(message: &str) -> Robj {
+        let mut condition = List::from_names_and_values(["message", "call"], [r!(message), r!(())])
+            .map(|list| list.into_robj())
+            .unwrap_or_else(|_| r!(message));
+        let _ = condition.set_class(["churon_batch_error", "error", "condition"]);
+        condition
+    }
+
+    /// Remove a leading batch axis of length one from a tensor output, keeping the
+    /// remaining dimensions as a `dim` attribute
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn drop_batch_axis(output_name: &str, robj: &mut Robj, value: &DynValue) -> ChurOnResult<()> {
+        let Some(shape) = value.dtype().tensor_shape() else {
+            return Ok(());
+        };
+        if shape.first() != Some(&1) || shape.len() < 3 {
+            // Rank one or two outputs are already plain vectors once the batch axis is gone
+            return Ok(());
+        }
+        let dims: Vec<i32> = shape[1..].iter().map(|&d| d as i32).collect();
+        robj.set_attrib("dim", dims).map_err(|e| {
+            ChurOnError::DataConversion(format!(
+                "Failed to set dimensions of output '{}': {}",
+                output_name, e
+            ))
+        })?;
+        Ok(())
+    }
+
+    /// Maps become named numeric vectors keyed by the map keys
+    #[cfg(not(target_arch = "wasm32"))]
+    fn map_to_r(
+        output_name: &str,
+        value: &DynValue,
+        key_type: TensorElementType,
+        value_type: TensorElementType,
+    ) -> ChurOnResult<Robj> {
+        let extract_error = |e: ort::Error| {
+            ChurOnError::DataConversion(format!(
+                "Failed to extract map output '{}': {}",
+                output_name, e
+            ))
+        };
+        fn named<K: ToString, V: Into<f64>>(pairs: Vec<(K, V)>) -> (Vec<String>, Vec<f64>) {
+            pairs.into_iter().map(|(k, v)| (k.to_string(), v.into())).unzip()
+        }
+
+        let (keys, values) = match (key_type, value_type) {
+            (TensorElementType::Int64, TensorElementType::Float32) => {
+                named(value.try_extract_key_values::<i64, f32>().map_err(extract_error)?)
+            }
+            (TensorElementType::Int64, TensorElementType::Float64) => {
+                named(value.try_extract_key_values::<i64, f64>().map_err(extract_error)?)
+            }
+            (TensorElementType::Int64, TensorElementType::Int64) => value
+                .try_extract_key_values::<i64, i64>()
+                .map_err(extract_error)?
+                .into_iter()
+                .map(|(k, v)| (k.to_string(), v as f64))
+                .unzip(),
+            (TensorElementType::String, TensorElementType::Float32) => {
+                named(value.try_extract_key_values::<String, f32>().map_err(extract_error)?)
+            }
+            (TensorElementType::String, TensorElementType::Float64) => {
+                named(value.try_extract_key_values::<String, f64>().map_err(extract_error)?)
+            }
+            (TensorElementType::String, TensorElementType::Int64) => value
+                .try_extract_key_values::<String, i64>()
+                .map_err(extract_error)?
+                .into_iter()
+                .map(|(k, v)| (k, v as f64))
+                .unzip(),
+            (key, value) => {
+                return Err(ChurOnError::DataConversion(format!(
+                    "Unsupported map type map({}, {}) for output '{}'",
+                    key, value, output_name
+                )))
+            }
+        };
+
+        let mut robj = Doubles::from_values(values).into_robj();
+        robj.set_names(keys).map_err(|e| {
+            ChurOnError::DataConversion(format!(
+                "Failed to name map output '{}': {}",
+                output_name, e
+            ))
+        })?;
+        Ok(robj)
+    }
+}
+
+/// Where a single `RSession::run` call spent its time, and how much tensor data it
+/// moved. Sequence and map values are not counted in the byte totals.
+#[derive(Debug, Default)]
+struct RunStats {
+    prepare_input_tensors: Duration,
+    convert_to_ort_values: Duration,
+    session_run: Duration,
+    extract_outputs: Duration,
+    input_bytes: usize,
+    output_bytes: usize,
+}
+
+impl RunStats {
+    fn attach(&self, result: &mut List) -> ChurOnResult<()> {
+        let phases = [
+            ("prepare_input_tensors", self.prepare_input_tensors),
+            ("convert_to_ort_values", self.convert_to_ort_values),
+            ("session_run", self.session_run),
+            ("extract_outputs", self.extract_outputs),
+        ];
+        let total: Duration = phases.iter().map(|(_, d)| *d).sum();
+        let mut timing: Robj = phases
+            .iter()
+            .map(|(_, d)| d.as_secs_f64() * 1000.0)
+            .chain(std::iter::once(total.as_secs_f64() * 1000.0))
+            .collect::<Vec<f64>>()
+            .into();
+        let mut bytes: Robj = vec![self.input_bytes as f64, self.output_bytes as f64].into();
+        let names: Vec<&str> = phases.iter().map(|(name, _)| *name).chain(["total"]).collect();
+        let fail = |e: extendr_api::Error| {
+            ChurOnError::DataConversion(format!("Failed to attach run statistics: {}", e))
+        };
+        timing.set_attrib("names", names).map_err(fail)?;
+        bytes.set_attrib("names", vec!["input", "output"]).map_err(fail)?;
+        result.set_attrib("timing_ms", timing).map_err(fail)?;
+        result.set_attrib("bytes", bytes).map_err(fail)?;
+        Ok(())
+    }
+}
+
+/// Per-iteration phase timings collected by `RSession::benchmark`, in seconds
+#[derive(Debug, Default)]
+struct BenchmarkTimings {
+    input_conversion: Vec<f64>,
+    run: Vec<f64>,
+    output_conversion: Vec<f64>,
+}
+
+impl BenchmarkTimings {
+    fn record(&mut self, input_conversion: Duration, run: Duration, output_conversion: Duration) {
+        self.input_conversion.push(input_conversion.as_secs_f64());
+        self.run.push(run.as_secs_f64());
+        self.output_conversion.push(output_conversion.as_secs_f64());
+    }
+
+    /// Nearest-rank percentile of ascending `sorted`, with `p` in [0, 100]
+    fn percentile(sorted: &[f64], p: f64) -> f64 {
+        let rank = (p / 100.0 * sorted.len() as f64).ceil() as usize;
+        sorted[rank.clamp(1, sorted.len()) - 1]
+    }
+
+    fn mean_ms(times: &[f64]) -> f64 {
+        times.iter().sum::<f64>() / times.len() as f64 * 1000.0
+    }
+
+    /// Latency distribution in milliseconds, throughput in runs per second and the
+    /// mean time of each phase in milliseconds
+    fn summary(&self, warmup: i32) -> List {
+        let mut latency: Vec<f64> = (0..self.run.len())
+            .map(|i| self.input_conversion[i] + self.run[i] + self.output_conversion[i])
+            .collect();
+        latency.sort_by(f64::total_cmp);
+        let ms = |p: f64| Self::percentile(&latency, p) * 1000.0;
+
+        list!(
+            iterations = latency.len() as i32,
+            warmup = warmup,
+            latency_ms = list!(
+                min = ms(0.0),
+                median = ms(50.0),
+                p90 = ms(90.0),
+                p99 = ms(99.0),
+                max = ms(100.0),
+                mean = Self::mean_ms(&latency)
+            ),
+            throughput = latency.len() as f64 / latency.iter().sum::<f64>(),
+            phase_ms = list!(
+                input_conversion = Self::mean_ms(&self.input_conversion),
+                run = Self::mean_ms(&self.run),
+                output_conversion = Self::mean_ms(&self.output_conversion)
+            )
+        )
+    }
+}
+
+/// Minimal JSON value, enough to read the Chrome traces written by ORT's profiler
+/// without pulling a JSON crate into the vendored dependencies
+#[derive(Debug, Clone, PartialEq)]
+enum Json {
+    Null,
+    Bool(bool),
+    Number(f64),
+    String(String),
+    Array(Vec<Json>),
+    Object(Vec<(String, Json)>),
+}
+
+impl Json {
+    fn parse(text: &str) -> ChurOnResult<Json> {
+        let mut parser = JsonParser {
+            bytes: text.as_bytes(),
+            pos: 0,
+        };
+        let value = parser.value()?;
+        parser.skip_whitespace();
+        if parser.pos != parser.bytes.len() {
+            return Err(parser.error("trailing characters"));
+        }
+        Ok(value)
+    }
+
+    fn get(&self, key: &str) -> Option<&Json> {
+        match self {
+            Json::Object(fields) => fields.iter().find(|(k, _)| k == key).map(|(_, v)| v),
+            _ => None,
+        }
+    }
+
+    fn as_str(&self) -> Option<&str> {
+        match self {
+            Json::String(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    fn as_f64(&self) -> Option<f64> {
+        match self {
+            Json::Number(n) => Some(*n),
+            _ => None,
+        }
+    }
+
+    fn as_array(&self) -> Option<&[Json]> {
+        match self {
+            Json::Array(items) => Some(items),
+            _ => None,
+        }
+    }
+}
+
+struct JsonParser<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl JsonParser<'_> {
+    fn error(&self, what: &str) -> ChurOnError {
+        ChurOnError::DataConversion(format!("Invalid profiling trace: {} at byte {}", what, self.pos))
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.bytes.get(self.pos).is_some_and(|b| b.is_ascii_whitespace()) {
+            self.pos += 1;
+        }
+    }
+
+    fn expect(&mut self, literal: &str) -> ChurOnResult<()> {
+        if self.bytes[self.pos..].starts_with(literal.as_bytes()) {
+            self.pos += literal.len();
+            Ok(())
+        } else {
+            Err(self.error(&format!("expected '{}'", literal)))
+        }
+    }
+
+    fn value(&mut self) -> ChurOnResult<Json> {
+        self.skip_whitespace();
+        match self.bytes.get(self.pos) {
+            Some(b'n') => self.expect("null").map(|_| Json::Null),
+            Some(b't') => self.expect("true").map(|_| Json::Bool(true)),
+            Some(b'f') => self.expect("false").map(|_| Json::Bool(false)),
+            Some(b'"') => self.string().map(Json::String),
+            Some(b'[') => {
+                self.pos += 1;
+                let mut items = Vec::new();
+                while !self.close(b']', items.is_empty())? {
+                    items.push(self.value()?);
+                }
+                Ok(Json::Array(items))
+            }
+            Some(b'{') => {
+                self.pos += 1;
+                let mut fields = Vec::new();
+                while !self.close(b'}', fields.is_empty())? {
+                    self.skip_whitespace();
+                    let key = self.string()?;
+                    self.skip_whitespace();
+                    self.expect(":")?;
+                    fields.push((key, self.value()?));
+                }
+                Ok(Json::Object(fields))
+            }
+            Some(_) => self.number(),
+            None => Err(self.error("unexpected end of input")),
+        }
+    }
+
+    /// Consume the closing bracket of a container, or the comma before its next
+    /// element. Returns whether the container ended.
+    fn close(&mut self, bracket: u8, first: bool) -> ChurOnResult<bool> {
+        self.skip_whitespace();
+        match self.bytes.get(self.pos) {
+            Some(&b) if b == bracket => {
+                self.pos += 1;
+                Ok(true)
+            }
+            Some(_) if first => Ok(false),
+            Some(b',') => {
+                self.pos += 1;
+                Ok(false)
+            }
+            _ => Err(self.error("expected ',' or closing bracket")),
+        }
+    }
+
+    fn string(&mut self) -> ChurOnResult<String> {
+        self.expect("\"")?;
+        let mut out = String::new();
+        loop {
+            let start = self.pos;
+            while !matches!(self.bytes.get(self.pos), Some(b'"' | b'\\') | None) {
+                self.pos += 1;
+            }
+            out.push_str(
+                std::str::from_utf8(&self.bytes[start..self.pos]).map_err(|_| self.error("invalid UTF-8"))?,
+            );
+            match self.bytes.get(self.pos) {
+                Some(b'"') => {
+                    self.pos += 1;
+                    return Ok(out);
+                }
+                Some(b'\\') => {
+                    let escape = *self.bytes.get(self.pos + 1).ok_or_else(|| self.error("unterminated escape"))?;
+                    self.pos += 2;
+                    match escape {
+                        b'n' => out.push('\n'),
+                        b't' => out.push('\t'),
+                        b'r' => out.push('\r'),
+                        b'b' => out.push('\u{8}'),
+                        b'f' => out.push('\u{c}'),
+                        b'u' => {
+                            let hex = self
+                                .bytes
+                                .get(self.pos..self.pos + 4)
+                                .and_then(|h| std::str::from_utf8(h).ok())
+                                .and_then(|h| u32::from_str_radix(h, 16).ok())
+                                .ok_or_else(|| self.error("invalid unicode escape"))?;
+                            self.pos += 4;
+                            out.push(char::from_u32(hex).unwrap_or(char::REPLACEMENT_CHARACTER));
+                        }
+                        other => out.push(other as char),
+                    }
+                }
+                _ => return Err(self.error("unterminated string")),
+            }
+        }
+    }
+
+    fn number(&mut self) -> ChurOnResult<Json> {
+        let start = self.pos;
+        while self
+            .bytes
+            .get(self.pos)
+            .is_some_and(|b| b.is_ascii_digit() || matches!(b, b'-' | b'+' | b'.' | b'e' | b'E'))
+        {
+            self.pos += 1;
+        }
+        std::str::from_utf8(&self.bytes[start..self.pos])
+            .ok()
+            .and_then(|n| n.parse().ok())
+            .map(Json::Number)
+            .ok_or_else(|| self.error("unexpected character"))
+    }
+}
+
+/// One kernel execution recorded in an ORT profiling trace
+#[derive(Debug, Clone, PartialEq)]
+struct NodeProfile {
+    node: String,
+    op_type: String,
+    provider: String,
+    start_us: f64,
+    duration_us: f64,
+    input_shapes: String,
+    output_shapes: String,
+}
+
+impl NodeProfile {
+    /// Kernel executions in a Chrome-trace JSON written by ORT's profiler. Session
+    /// events and the fences around each kernel are skipped.
+    fn parse_trace(json: &str) -> ChurOnResult<Vec<NodeProfile>> {
+        let trace = Json::parse(json)?;
+        let events = trace.as_array().ok_or_else(|| {
+            ChurOnError::DataConversion("Profiling trace is not a JSON array of events".to_string())
+        })?;
+
+        let text = |args: Option<&Json>, key: &str| {
+            args.and_then(|a| a.get(key))
+                .and_then(|v| v.as_str())
+                .unwrap_or_default()
+                .to_string()
+        };
+        let number = |event: &Json, key: &str| event.get(key).and_then(|v| v.as_f64()).unwrap_or(f64::NAN);
+        Ok(events
+            .iter()
+            .filter(|event| event.get("cat").and_then(|v| v.as_str()) == Some("Node"))
+            .filter_map(|event| {
+                let node = event.get("name")?.as_str()?.strip_suffix("_kernel_time")?;
+                let args = event.get("args");
+                Some(NodeProfile {
+                    node: node.to_string(),
+                    op_type: text(args, "op_name"),
+                    provider: text(args, "provider"),
+                    start_us: number(event, "ts"),
+                    duration_us: number(event, "dur"),
+                    input_shapes: Self::format_shapes(args.and_then(|a| a.get("input_type_shape"))),
+                    output_shapes: Self::format_shapes(args.and_then(|a| a.get("output_type_shape"))),
+                })
+            })
+            .collect())
+    }
+
+    /// Render `[{"float": [1, 3]}, ...]` as `float[1,3]; ...`
+    fn format_shapes(shapes: Option<&Json>) -> String {
+        shapes
+            .and_then(|v| v.as_array())
+            .unwrap_or_default()
+            .iter()
+            .filter_map(|shape| match shape {
+                Json::Object(fields) => Some(fields),
+                _ => None,
+            })
+            .flatten()
+            .map(|(element_type, dims)| {
+                let dims: Vec<String> = dims
+                    .as_array()
+                    .unwrap_or_default()
+                    .iter()
+                    .filter_map(|d| d.as_f64())
+                    .map(|d| (d as i64).to_string())
+                    .collect();
+                format!("{}[{}]", element_type, dims.join(","))
+            })
+            .collect::<Vec<_>>()
+            .join("; ")
+    }
+
+    /// Columns of a profile data frame, assembled in R
+    fn to_r(profiles: &[NodeProfile]) -> List {
+        list!(
+            node = profiles.iter().map(|p| p.node.as_str()).collect::<Vec<_>>(),
+            op_type = profiles.iter().map(|p| p.op_type.as_str()).collect::<Vec<_>>(),
+            provider = profiles.iter().map(|p| p.provider.as_str()).collect::<Vec<_>>(),
+            start_us = profiles.iter().map(|p| p.start_us).collect::<Vec<_>>(),
+            duration_us = profiles.iter().map(|p| p.duration_us).collect::<Vec<_>>(),
+            input_shapes = profiles.iter().map(|p| p.input_shapes.as_str()).collect::<Vec<_>>(),
+            output_shapes = profiles.iter().map(|p| p.output_shapes.as_str()).collect::<Vec<_>>()
+        )
+    }
+}
+
+/// Cargo features compiled into this build of churon
+const COMPILED_FEATURES: &[(&str, bool)] = &[
+    ("ort-cuda", cfg!(feature = "ort-cuda")),
+    ("ort-tensorrt", cfg!(feature = "ort-tensorrt")),
+    ("ort-directml", cfg!(feature = "ort-directml")),
+    ("ort-onednn", cfg!(feature = "ort-onednn")),
+    ("ort-coreml", cfg!(feature = "ort-coreml")),
+    ("ort-rocm", cfg!(feature = "ort-rocm")),
+    ("ort-openvino", cfg!(feature = "ort-openvino")),
+];
+
+/// Version of the `ort` crate churon is built against, as pinned in Cargo.toml;
+/// `test_ort_crate_version_matches_manifest` keeps the two in step
+const ORT_CRATE_VERSION: &str = "2.0.0-rc.10";
+
+/// Describe the ONNX Runtime library. This only reads the current state: before
+/// any session is created it reports the library that would be loaded, without
+/// loading it or committing its path.
+#[extendr]
+fn ort_runtime_info() -> List {
+    let features: Vec<String> = COMPILED_FEATURES
+        .iter()
+        .filter(|(_, enabled)| *enabled)
+        .map(|(name, _)| name.to_string())
+        .collect();
+
+    #[cfg(target_arch = "wasm32")]
+    {
+        list!(
+            initialized = false,
+            init_error = "ONNX Runtime is not supported on WASM",
+            features = features
+        )
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        let outcome = OrtRuntime::outcome();
+        let init_error = match &outcome {
+            Some(Err(e)) => Some(e.to_string()),
+            _ => None,
+        };
+        let loaded_path = OrtRuntime::library_path();
+        let located_path = match &outcome {
+            None => RuntimeLocator::locate().ok().map(|path| path.to_string_lossy().into_owned()),
+            Some(_) => None,
+        };
+        let (version, build_info, providers) = match &loaded_path {
+            Some(path) => (
+                OrtRuntime::loaded_version(path).ok(),
+                Some(ort::info().to_string()),
+                OrtRuntime::available_providers().unwrap_or_default(),
+            ),
+            None => (None, None, Vec::new()),
+        };
+
+        list!(
+            initialized = loaded_path.is_some(),
+            init_error = init_error,
+            loaded_path = loaded_path,
+            located_path = located_path,
+            version = version,
+            api_version = ort::MINOR_VERSION as i32,
+            ort_crate_version = ORT_CRATE_VERSION,
+            build_info = build_info,
+            features = features,
+            available_providers = providers
+        )
+    }
+}
+
+/// Oldest ONNX Runtime release providing the C API version `ort` requests
+#[extendr]
+fn ort_required_version() -> String {
+    #[cfg(target_arch = "wasm32")]
+    {
+        String::new()
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        format!("1.{}.0", ort::MINOR_VERSION)
+    }
+}
+
+/// Set the severity below which environment-level log messages are dropped,
+/// returning the previous severity
+#[extendr]
+fn ort_set_log_level(level: &str) -> extendr_api::Result<String> {
+    let severity = LogSeverity::parse(level)?;
+    Ok(ENVIRONMENT_LOG.set_min_severity(severity).as_str().to_string())
+}
+
+/// Buffered environment-level log messages as data frame columns
+#[extendr]
+fn ort_environment_logs(#[default = "FALSE"] clear: bool) -> List {
+    LogBuffer::to_r(&ENVIRONMENT_LOG.entries(false, clear))
+}
+
+/// Per-node kernel timings from an ORT profiling trace, as data frame columns
+#[extendr]
+fn ort_parse_profile(path: &str) -> extendr_api::Result<List> {
+    let json = std::fs::read_to_string(path).map_err(|e| {
+        ChurOnError::Validation(format!("Failed to read profiling trace {}: {}", path, e))
+    })?;
+    Ok(NodeProfile::to_r(&NodeProfile::parse_trace(&json)?))
+}
+
+/// Operators churon registers with every session, as data frame columns
+#[extendr]
+fn churon_builtin_ops() -> List {
+    let attributes: Vec<Rstr> = BuiltinOp::ALL
+        .iter()
+        .map(|op| op.attribute().map_or_else(Rstr::na, Rstr::from))
+        .collect();
+    list!(
+        domain = vec![CHURON_DOMAIN; BuiltinOp::ALL.len()],
+        name = BuiltinOp::ALL.iter().map(|op| op.as_str()).collect::<Vec<_>>(),
+        input = vec!["string"; BuiltinOp::ALL.len()],
+        output = BuiltinOp::ALL.iter().map(|op| op.output_type()).collect::<Vec<_>>(),
+        attribute = Strings::from_values(attributes)
+    )
+}
+
+/// Sessions in the session cache as data frame columns
+#[extendr]
+fn ort_cache_list() -> List {
+    SessionCache::lock().to_r()
+}
+
+/// Evict cached sessions for `path`, or all of them, returning how many were evicted
+#[extendr]
+fn ort_cache_evict(#[default = "NULL"] path: Nullable<String>) -> i32 {
+    SessionCache::lock().evict(path.into_option().as_deref()) as i32
+}
+
+/// Set the cache's memory limit in bytes (`Inf` for none), evicting least recently
+/// used sessions to fit, and return the previous limit
+#[extendr]
+fn ort_cache_max_memory(bytes: f64) -> extendr_api::Result<f64> {
+    if bytes.is_nan() || bytes < 0.0 {
+        return Err(ChurOnError::Validation("max_memory must be a non-negative number of bytes".to_string()).into());
+    }
+    let mut cache = SessionCache::lock();
+    let previous = cache.max_bytes.map_or(f64::INFINITY, |max| max as f64);
+    cache.max_bytes = bytes.is_finite().then_some(bytes as u64);
+    cache.enforce_limit();
+    Ok(previous)
+}
+
+/// Whether `x` is an external pointer that no longer points anywhere, as with
+/// sessions read back by `readRDS()` or received by another R process
+#[extendr]
+fn is_null_external_pointer(x: Robj) -> bool {
+    x.rtype() == Rtype::ExternalPtr && unsafe { x.external_ptr_addr::<c_void>().is_null() }
+}
+
+/// Retry ONNX Runtime initialization from the library at `path`
+#[extendr]
+fn ort_reinitialize(path: &str) -> extendr_api::Result<String> {
+    #[cfg(target_arch = "wasm32")]
+    {
+        let _ = path;
+        Err(extendr_api::Error::EvalError("ONNX Runtime is not supported on WASM".into()))
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        Ok(OrtRuntime::reinitialize(path)?)
+    }
+}
+
+extendr_module! {
+    mod churon;
+    fn ort_reinitialize;
+    fn ort_set_log_level;
+    fn ort_environment_logs;
+    fn ort_parse_profile;
+    fn ort_runtime_info;
+    fn ort_required_version;
+    fn is_null_external_pointer;
+    fn ort_cache_list;
+    fn ort_cache_evict;
+    fn ort_cache_max_memory;
+    fn churon_builtin_ops;
+    impl RSession;
+    impl RSessionPool;
+    impl RInferenceHandle;
+    impl RBinding;
+    impl TensorInfo;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tensor_info_creation() {
+        let tensor_info = TensorInfo::new(
+            "test_tensor".to_string(),
             vec![2, 3, 4],
             "Float32".to_string(),
         );
@@ -724,4 +5061,112 @@ mod tests {
         assert_eq!(tensor_info.get_shape(), vec![2, 3, 4]);
         assert_eq!(tensor_info.get_data_type(), "Float32");
     }
+
+    #[test]
+    fn test_runtime_version_compatibility() {
+        let required = ort::MINOR_VERSION;
+        assert!(OrtRuntime::check_compatible(&format!("1.{}.0", required)).is_ok());
+        assert!(OrtRuntime::check_compatible(&format!("1.{}.2", required + 1)).is_ok());
+        assert!(OrtRuntime::check_compatible(&format!("1.{}.1", required - 1)).is_err());
+        assert!(OrtRuntime::check_compatible("2.0.0").is_err());
+        assert!(OrtRuntime::check_compatible("not-a-version").is_err());
+    }
+
+    #[test]
+    fn test_ort_crate_version_matches_manifest() {
+        let pin = format!("ort = {{ version = \"={}\"", ORT_CRATE_VERSION);
+        assert!(include_str!("../Cargo.toml").contains(&pin));
+        let locked = format!("name = \"ort\"\nversion = \"{}\"", ORT_CRATE_VERSION);
+        assert!(include_str!("../Cargo.lock").contains(&locked));
+    }
+
+    #[test]
+    #[cfg(target_os = "linux")]
+    fn test_versioned_library_names() {
+        assert!(RuntimeLocator::is_versioned_library("libonnxruntime.so.1"));
+        assert!(RuntimeLocator::is_versioned_library("libonnxruntime.so.1.23.2"));
+        assert!(!RuntimeLocator::is_versioned_library("libonnxruntime.so"));
+        assert!(!RuntimeLocator::is_versioned_library("libonnxruntime_providers_shared.so"));
+    }
+
+    #[test]
+    fn test_pad_sequence_sides() {
+        let sequence = ArrayD::from_shape_vec(IxDyn(&[2]), vec![1.0f32, 2.0]).unwrap();
+        let right = RSession::pad_sequence(sequence.view(), 4, 0.0, PadSide::Right);
+        assert_eq!(right.shape(), &[1, 4]);
+        assert_eq!(right.iter().cloned().collect::<Vec<_>>(), vec![1.0, 2.0, 0.0, 0.0]);
+        let left = RSession::pad_sequence(sequence.view(), 4, -1.0, PadSide::Left);
+        assert_eq!(left.iter().cloned().collect::<Vec<_>>(), vec![-1.0, -1.0, 1.0, 2.0]);
+    }
+
+    #[test]
+    fn test_benchmark_percentiles() {
+        let sorted: Vec<f64> = (1..=10).map(f64::from).collect();
+        assert_eq!(BenchmarkTimings::percentile(&sorted, 0.0), 1.0);
+        assert_eq!(BenchmarkTimings::percentile(&sorted, 50.0), 5.0);
+        assert_eq!(BenchmarkTimings::percentile(&sorted, 90.0), 9.0);
+        assert_eq!(BenchmarkTimings::percentile(&sorted, 99.0), 10.0);
+        assert_eq!(BenchmarkTimings::percentile(&sorted, 100.0), 10.0);
+        assert_eq!(BenchmarkTimings::percentile(&[3.0], 50.0), 3.0);
+    }
+
+    #[test]
+    fn test_pin_dimensions() {
+        let symbols: Vec<String> = ["batch", "", "seq"].iter().map(|s| s.to_string()).collect();
+        let overrides = vec![("batch".to_string(), 1), ("seq".to_string(), 128), ("other".to_string(), 4)];
+        assert_eq!(RSession::pin_dimensions(&[-1, 3, -1], &symbols, &overrides), vec![1, 3, 128]);
+        assert_eq!(RSession::pin_dimensions(&[-1, 3, 64], &symbols, &overrides[..1]), vec![1, 3, 64]);
+        assert_eq!(RSession::pin_dimensions(&[-1], &[], &overrides), vec![-1]);
+        assert!(RSession::is_denotation("DATA_BATCH"));
+        assert!(!RSession::is_denotation("batch"));
+    }
+
+    #[test]
+    fn test_builtin_string_ops() {
+        let strings = vec!["Hello World".to_string(), "".to_string(), "a  b c".to_string()];
+        let (shape, lower) = BuiltinOp::StringLower.apply(&[3], &strings, 0).unwrap();
+        assert_eq!(shape, vec![3]);
+        assert_eq!(
+            lower,
+            BuiltinOutput::Strings(vec!["hello world".to_string(), "".to_string(), "a  b c".to_string()])
+        );
+        let (_, lengths) = BuiltinOp::StringLength.apply(&[3], &strings, 0).unwrap();
+        assert_eq!(lengths, BuiltinOutput::Int64(vec![11, 0, 6]));
+
+        let (shape, tokens) = BuiltinOp::WhitespaceTokenize.apply(&[3], &strings, 0).unwrap();
+        assert_eq!(shape, vec![3, 3]);
+        let expected = ["Hello", "World", "", "", "", "", "a", "b", "c"];
+        assert_eq!(tokens, BuiltinOutput::Strings(expected.iter().map(|s| s.to_string()).collect()));
+        let (shape, _) = BuiltinOp::WhitespaceTokenize.apply(&[3], &strings, 1).unwrap();
+        assert_eq!(shape, vec![3, 1]);
+
+        // FNV-1a reference values, so buckets stay stable across releases
+        assert_eq!(BuiltinOp::fnv1a(""), 0xcbf29ce484222325);
+        assert_eq!(BuiltinOp::fnv1a("a"), 0xaf63dc4c8601ec8c);
+        let (_, buckets) = BuiltinOp::StringHashBucket.apply(&[3], &strings, 7).unwrap();
+        let bucket = |s: &str| (BuiltinOp::fnv1a(s) % 7) as i64;
+        assert_eq!(buckets, BuiltinOutput::Int64(vec![bucket("Hello World"), bucket(""), bucket("a  b c")]));
+        assert!(BuiltinOp::StringHashBucket.apply(&[3], &strings, 0).is_err());
+    }
+
+    #[test]
+    fn test_parse_profile_trace() {
+        let trace = r#"[
+            {"cat": "Session", "name": "model_run", "ts": 10, "dur": 90, "args": {}},
+            {"cat": "Node", "name": "conv1_fence_before", "ts": 20, "dur": 0, "args": {"op_name": "Conv"}},
+            {"cat": "Node", "name": "conv1_kernel_time", "ts": 21, "dur": 42,
+             "args": {"op_name": "Conv", "provider": "CPUExecutionProvider",
+                      "input_type_shape": [{"float": [1, 1, 28, 28]}, {"float": [8, 1, 5, 5]}],
+                      "output_type_shape": [{"float": [1, 8, 28, 28]}]}}
+        ]"#;
+        let profiles = NodeProfile::parse_trace(trace).unwrap();
+        assert_eq!(profiles.len(), 1);
+        assert_eq!(profiles[0].node, "conv1");
+        assert_eq!(profiles[0].op_type, "Conv");
+        assert_eq!(profiles[0].provider, "CPUExecutionProvider");
+        assert_eq!(profiles[0].duration_us, 42.0);
+        assert_eq!(profiles[0].input_shapes, "float[1,1,28,28]; float[8,1,5,5]");
+        assert_eq!(profiles[0].output_shapes, "float[1,8,28,28]");
+        assert!(NodeProfile::parse_trace("{}").is_err());
+    }
 }
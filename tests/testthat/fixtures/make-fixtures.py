@@ -0,0 +1,165 @@
+"""Write the small ONNX models used by the tests.
+
+The models are encoded by hand so that regenerating them needs nothing
+beyond the Python standard library. Run from this directory:
+
+    python3 make-fixtures.py
+"""
+
+FLOAT, INT64, STRING = 1, 7, 8
+
+
+def varint(n):
+    out = bytearray()
+    while True:
+        byte = n & 0x7F
+        n >>= 7
+        if n:
+            out.append(byte | 0x80)
+        else:
+            out.append(byte)
+            return bytes(out)
+
+
+def field(number, value):
+    if isinstance(value, int):
+        return varint(number << 3) + varint(value)
+    if isinstance(value, str):
+        value = value.encode()
+    return varint(number << 3 | 2) + varint(len(value)) + value
+
+
+def message(*fields):
+    return b"".join(field(n, v) for n, v in fields if v is not None)
+
+
+def dim(value=None, param=None, denotation=None):
+    return message((1, value), (2, param), (3, denotation))
+
+
+def tensor_type(elem_type, dims=None):
+    shape = None if dims is None else message(*[(1, d) for d in dims])
+    return message((1, message((1, elem_type), (2, shape))))
+
+
+def sequence_type(element):
+    return message((4, message((1, element))))
+
+
+def map_type(key_type, value):
+    return message((5, message((1, key_type), (2, value))))
+
+
+def value_info(name, type_proto):
+    return message((1, name), (2, type_proto))
+
+
+def attribute_int(name, value):
+    return message((1, name), (3, value), (20, 2))
+
+
+def attribute_strings(name, values):
+    return message((1, name), *[(9, v) for v in values], (20, 8))
+
+
+def node(op_type, inputs, outputs, attributes=(), domain=None):
+    return message(
+        *[(1, i) for i in inputs],
+        *[(2, o) for o in outputs],
+        (3, outputs[0]),
+        (4, op_type),
+        *[(5, a) for a in attributes],
+        (7, domain),
+    )
+
+
+def model(name, nodes, inputs, outputs, opsets):
+    graph = message(
+        *[(1, n) for n in nodes],
+        (2, name),
+        *[(11, i) for i in inputs],
+        *[(12, o) for o in outputs],
+    )
+    return message(
+        (1, 8),
+        (2, "churon-fixtures"),
+        (7, graph),
+        *[(8, message((1, domain), (2, version))) for domain, version in opsets],
+    )
+
+
+def sequence_map_model():
+    vocabulary = ["a", "b", "c"]
+    float_tensor = tensor_type(FLOAT)
+    nodes = [
+        node("SequenceLength", ["tensors"], ["count"]),
+        node("ConcatFromSequence", ["tensors"], ["stacked"], [attribute_int("axis", 0)]),
+        node("SplitToSequence", ["stacked"], ["pieces"]),
+        node(
+            "DictVectorizer",
+            ["features"],
+            ["vector"],
+            [attribute_strings("string_vocabulary", vocabulary)],
+            domain="ai.onnx.ml",
+        ),
+        node(
+            "ZipMap",
+            ["vector"],
+            ["scores"],
+            [attribute_strings("classlabels_strings", vocabulary)],
+            domain="ai.onnx.ml",
+        ),
+    ]
+    inputs = [
+        value_info("tensors", sequence_type(float_tensor)),
+        value_info("features", map_type(STRING, tensor_type(FLOAT))),
+    ]
+    outputs = [
+        value_info("count", tensor_type(INT64, [])),
+        value_info("stacked", tensor_type(FLOAT, [dim(param="total")])),
+        value_info("pieces", sequence_type(float_tensor)),
+        value_info("vector", tensor_type(FLOAT, [dim(1), dim(3)])),
+        value_info("scores", sequence_type(map_type(STRING, tensor_type(FLOAT)))),
+    ]
+    return model("sequence_map", nodes, inputs, outputs, [("", 13), ("ai.onnx.ml", 1)])
+
+
+def ragged_model():
+    shape = [dim(param="batch"), dim(param="sequence")]
+    nodes = [node("Identity", ["ids"], ["ids_out"]), node("Identity", ["mask"], ["mask_out"])]
+    inputs = [value_info("ids", tensor_type(INT64, shape)), value_info("mask", tensor_type(INT64, shape))]
+    outputs = [
+        value_info("ids_out", tensor_type(INT64, shape)),
+        value_info("mask_out", tensor_type(INT64, shape)),
+    ]
+    return model("ragged", nodes, inputs, outputs, [("", 13)])
+
+
+def custom_ops_model():
+    shape = [dim(param="n")]
+    nodes = [
+        node("StringLower", ["text"], ["lower"], domain="ai.churon"),
+        node("Double", ["x"], ["doubled"], domain="ai.churon.r"),
+    ]
+    inputs = [value_info("text", tensor_type(STRING, shape)), value_info("x", tensor_type(FLOAT, shape))]
+    outputs = [value_info("lower", tensor_type(STRING, shape)), value_info("doubled", tensor_type(FLOAT, shape))]
+    return model("custom_ops", nodes, inputs, outputs, [("", 13), ("ai.churon", 1), ("ai.churon.r", 1)])
+
+
+def symbolic_dim_model():
+    batch = dim(param="batch", denotation="DATA_BATCH")
+    nodes = [node("Relu", ["x"], ["y"])]
+    inputs = [value_info("x", tensor_type(FLOAT, [batch, dim(4)]))]
+    outputs = [value_info("y", tensor_type(FLOAT, [batch, dim(4)]))]
+    return model("symbolic_dim", nodes, inputs, outputs, [("", 13)])
+
+
+if __name__ == "__main__":
+    for path, proto in [
+        ("sequence_map.onnx", sequence_map_model()),
+        ("ragged.onnx", ragged_model()),
+        ("custom_ops.onnx", custom_ops_model()),
+        ("symbolic_dim.onnx", symbolic_dim_model()),
+    ]:
+        with open(path, "wb") as f:
+            f.write(proto)